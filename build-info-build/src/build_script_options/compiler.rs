@@ -1,12 +1,15 @@
-use build_info_common::{chrono::NaiveDate, semver::Version, CompilerChannel, CompilerInfo};
+use build_info_common::{chrono::NaiveDate, semver::Version, CompilerChannel, CompilerInfo, Endianness, TargetInfo};
 use rustc_version::{version_meta, Channel};
 
-pub(crate) fn get_info() -> CompilerInfo {
-	let rustc_version = version_meta().unwrap();
+use crate::BuildInfoError;
+
+pub(crate) fn get_info() -> Result<CompilerInfo, BuildInfoError> {
+	let rustc_version = version_meta().map_err(|err| BuildInfoError::Compiler(err.into()))?;
 
 	// By serializing and reparsing the version, we break the version-lock between semver as provided
 	// by rustc_version and semver as provided and used by this crate.
-	let version = Version::parse(&rustc_version.semver.to_string()).unwrap();
+	let version =
+		Version::parse(&rustc_version.semver.to_string()).map_err(|err| BuildInfoError::Compiler(err.into()))?;
 
 	let channel = match rustc_version.channel {
 		Channel::Stable => CompilerChannel::Stable,
@@ -19,12 +22,216 @@ pub(crate) fn get_info() -> CompilerInfo {
 		.commit_date
 		.and_then(|date| NaiveDate::parse_from_str(&date, "%Y-%m-%d").ok());
 
-	CompilerInfo {
+	let rustflags = rustflags();
+	let flags = parse_rustflags(&rustflags);
+
+	let rustup_toolchain = non_empty_var("RUSTUP_TOOLCHAIN");
+	let pinned_toolchain = super::toolchain::pinned_toolchain();
+	if let Some(ref pin) = pinned_toolchain {
+		if super::toolchain::matches_pin(&pin.channel, &version, channel, commit_date) == Some(false) {
+			println!(
+				"cargo:warning=The active toolchain (rustc {}{}) differs from the toolchain {:?} pinned in {}",
+				version,
+				rustup_toolchain
+					.as_ref()
+					.map_or_else(String::new, |toolchain| format!(", {}", toolchain)),
+				pin.channel,
+				pin.path.display(),
+			);
+		}
+	}
+
+	Ok(CompilerInfo {
 		version,
 		commit_id: rustc_version.commit_hash,
 		commit_date,
 		channel,
 		host_triple: rustc_version.host,
 		target_triple: std::env::var("TARGET").unwrap_or_else(|_| "UNKNOWN".to_string()),
+		target: get_target_info()?,
+		rustflags,
+		rustc_wrapper: non_empty_var("RUSTC_WRAPPER"),
+		rustc_workspace_wrapper: non_empty_var("RUSTC_WORKSPACE_WRAPPER"),
+		linker: flags.linker.or_else(|| non_empty_var("RUSTC_LINKER")),
+		target_cpu: flags.target_cpu,
+		sanitizers: flags.sanitizers,
+		instrument_coverage: flags.instrument_coverage,
+		profile_generate: flags.profile_generate,
+		profile_use: flags.profile_use,
+		llvm_version: rustc_version.llvm_version.map(|llvm| llvm.to_string()),
+		cargo_version: super::toolchain::cargo_version(),
+		rustup_toolchain,
+		pinned_toolchain: pinned_toolchain.map(|pin| pin.channel),
+	})
+}
+
+fn non_empty_var(name: &str) -> Option<String> {
+	println!("cargo:rerun-if-env-changed={}", name);
+	std::env::var(name).ok().filter(|value| !value.is_empty())
+}
+
+/// Decodes the flags cargo passes to `rustc` from `CARGO_ENCODED_RUSTFLAGS`, falling back to `RUSTFLAGS` for versions
+/// of cargo that do not provide the former.
+fn rustflags() -> Vec<String> {
+	if let Ok(encoded) = std::env::var("CARGO_ENCODED_RUSTFLAGS") {
+		encoded
+			.split('\x1f')
+			.filter(|flag| !flag.is_empty())
+			.map(|flag| flag.to_string())
+			.collect()
+	} else {
+		std::env::var("RUSTFLAGS")
+			.map(|flags| flags.split_whitespace().map(|flag| flag.to_string()).collect())
+			.unwrap_or_default()
+	}
+}
+
+#[derive(Default, Eq, PartialEq, Debug)]
+struct RustFlags {
+	linker: Option<String>,
+	target_cpu: Option<String>,
+	sanitizers: Vec<String>,
+	instrument_coverage: bool,
+	profile_generate: bool,
+	profile_use: bool,
+}
+
+fn parse_rustflags(rustflags: &[String]) -> RustFlags {
+	let mut flags = RustFlags::default();
+
+	let mut args = rustflags.iter().map(|flag| flag.as_str());
+	while let Some(arg) = args.next() {
+		// Supports the forms `-C opt`, `-Copt`, `--codegen opt` and `--codegen=opt`, and likewise for `-Z`
+		let (kind, option) = if let Some(option) = arg.strip_prefix("--codegen=") {
+			('C', option)
+		} else if arg == "-C" || arg == "--codegen" {
+			('C', args.next().unwrap_or_default())
+		} else if arg == "-Z" {
+			('Z', args.next().unwrap_or_default())
+		} else if let Some(option) = arg.strip_prefix("-C") {
+			('C', option)
+		} else if let Some(option) = arg.strip_prefix("-Z") {
+			('Z', option)
+		} else {
+			continue;
+		};
+
+		let (key, value) = option
+			.split_once('=')
+			.map_or((option, None), |(key, value)| (key, Some(value)));
+		match (kind, key) {
+			('C', "linker") => flags.linker = value.map(|value| value.to_string()),
+			('C', "target-cpu") => flags.target_cpu = value.map(|value| value.to_string()),
+			('C', "instrument-coverage") | ('Z', "instrument-coverage") => {
+				flags.instrument_coverage = !matches!(value, Some("off" | "no" | "n" | "false"))
+			}
+			('C', "profile-generate") => flags.profile_generate = true,
+			('C', "profile-use") => flags.profile_use = true,
+			('Z', "sanitizer") => {
+				for sanitizer in value.unwrap_or_default().split(',').filter(|s| !s.is_empty()) {
+					if !flags.sanitizers.iter().any(|s| s == sanitizer) {
+						flags.sanitizers.push(sanitizer.to_string());
+					}
+				}
+			}
+			_ => (),
+		}
+	}
+
+	flags.sanitizers.sort();
+	flags
+}
+
+fn get_target_info() -> Result<TargetInfo, BuildInfoError> {
+	let cfg = BuildInfoError::env_var;
+	let cfg_list = |name: &str| {
+		std::env::var(name).map_or_else(
+			|_| Vec::new(),
+			|values| {
+				values
+					.split(',')
+					.filter(|value| !value.is_empty())
+					.map(|value| value.to_string())
+					.collect()
+			},
+		)
+	};
+
+	let endian = match cfg("CARGO_CFG_TARGET_ENDIAN")?.as_str() {
+		"big" => Endianness::Big,
+		"little" => Endianness::Little,
+		endian => {
+			return Err(BuildInfoError::InvalidEnvVar {
+				name: "CARGO_CFG_TARGET_ENDIAN".to_string(),
+				value: endian.to_string(),
+			})
+		}
+	};
+	let pointer_width = cfg("CARGO_CFG_TARGET_POINTER_WIDTH")?;
+	let pointer_width = pointer_width.parse().map_err(|_| BuildInfoError::InvalidEnvVar {
+		name: "CARGO_CFG_TARGET_POINTER_WIDTH".to_string(),
+		value: pointer_width.clone(),
+	})?;
+
+	Ok(TargetInfo {
+		arch: cfg("CARGO_CFG_TARGET_ARCH")?,
+		os: cfg("CARGO_CFG_TARGET_OS")?,
+		family: cfg_list("CARGO_CFG_TARGET_FAMILY"),
+		env: std::env::var("CARGO_CFG_TARGET_ENV").unwrap_or_default(),
+		vendor: cfg("CARGO_CFG_TARGET_VENDOR")?,
+		endian,
+		pointer_width,
+		features: cfg_list("CARGO_CFG_TARGET_FEATURE"),
+	})
+}
+
+#[cfg(test)]
+mod test {
+	use pretty_assertions::assert_eq;
+
+	use super::*;
+
+	fn parse(flags: &[&str]) -> RustFlags {
+		parse_rustflags(&flags.iter().map(|flag| flag.to_string()).collect::<Vec<_>>())
+	}
+
+	#[test]
+	fn no_flags() {
+		assert_eq!(parse(&[]), RustFlags::default());
+	}
+
+	#[test]
+	fn codegen_flags() {
+		assert_eq!(
+			parse(&[
+				"-C",
+				"target-cpu=x86-64",
+				"-Ctarget-cpu=native",
+				"--codegen=linker=clang",
+				"-Cinstrument-coverage",
+				"--codegen",
+				"profile-use=/tmp/merged.profdata",
+			]),
+			RustFlags {
+				linker: Some("clang".to_string()),
+				target_cpu: Some("native".to_string()),
+				instrument_coverage: true,
+				profile_use: true,
+				..Default::default()
+			}
+		);
+	}
+
+	#[test]
+	fn disabled_coverage() {
+		assert!(!parse(&["-Cinstrument-coverage=off"]).instrument_coverage);
+	}
+
+	#[test]
+	fn sanitizers() {
+		assert_eq!(
+			parse(&["-Zsanitizer=thread", "-Z", "sanitizer=address,thread"]).sanitizers,
+			vec!["address", "thread"]
+		);
 	}
 }
@@ -1,4 +1,12 @@
-use build_info_common::CrateInfo;
+use std::{
+	collections::HashMap,
+	path::{Component, Path, PathBuf},
+};
+
+use build_info_common::{semver::Version, CrateInfo};
+use cargo_metadata::{CargoOpt, DependencyKind, Metadata, MetadataCommand, Node, Package, PackageId};
+
+use crate::BuildInfoError;
 
 impl crate::BuildScriptOptions {
 	/// Enables and disables dependency collection.
@@ -13,36 +21,492 @@ impl crate::BuildScriptOptions {
 
 pub(crate) struct Manifest {
 	pub crate_info: CrateInfo,
-	pub workspace_root: String,
+	pub workspace_root: PathBuf,
 }
 
-pub(crate) fn read_manifest() -> Manifest {
-	let mut enabled_features = vec![];
+pub(crate) fn read_manifest() -> Result<Manifest, BuildInfoError> {
+	let cargo_toml = super::cargo_toml()?;
+	let manifest: toml::Value = std::fs::read_to_string(&cargo_toml)
+		.map_err(|err| BuildInfoError::Manifest(err.into()))?
+		.parse()
+		.map_err(|err: toml::de::Error| BuildInfoError::Manifest(err.into()))?;
 
-	for (key, _) in std::env::vars() {
-		if let Some(p) = key.strip_prefix("CARGO_FEATURE_") {
-			enabled_features.push(p.to_ascii_lowercase());
-		}
-	}
+	let manifest_dir = cargo_toml.parent().unwrap_or_else(|| Path::new("."));
+	let workspace_root = workspace_root(manifest_dir, &manifest, &|path| {
+		std::fs::read_to_string(path).ok()?.parse().ok()
+	});
+	let path_in_workspace = relative_path(manifest_dir, &workspace_root);
+
+	let available_features = available_features(&manifest);
+
+	let enabled_features = enabled_features(
+		&available_features,
+		std::env::vars().filter_map(|(key, _)| key.strip_prefix("CARGO_FEATURE_").map(|p| p.to_string())),
+	);
 
+	let version = BuildInfoError::env_var("CARGO_PKG_VERSION")?;
 	let crate_info = CrateInfo {
-		name: std::env::var("CARGO_PKG_NAME").expect("CARGO_PKG_NAME"),
-		version: std::env::var("CARGO_PKG_VERSION")
-			.expect("CARGO_PKG_VERSION")
-			.parse()
-			.expect("CARGO_PKG_VERSION: parse"),
+		name: BuildInfoError::env_var("CARGO_PKG_NAME")?,
+		version: version.parse().map_err(|_| BuildInfoError::InvalidEnvVar {
+			name: "CARGO_PKG_VERSION".to_string(),
+			value: version.clone(),
+		})?,
+		git_version: None,
 		authors: std::env::var("CARGO_PKG_AUTHORS").map_or_else(
 			|_| Vec::new(),
 			|authors| authors.split(':').map(|x| x.to_string()).collect::<Vec<_>>(),
 		),
 		enabled_features,
-		available_features: Default::default(),
-		dependencies: Default::default(),
+		available_features,
+		dependencies: Vec::new(),
 		license: std::env::var("CARGO_PKG_LICENSE").ok(),
+		workspace_root: Some(
+			workspace_root
+				.to_str()
+				.ok_or_else(|| BuildInfoError::NonUtf8Path {
+					path: workspace_root.clone(),
+				})?
+				.to_string(),
+		),
+		path_in_workspace,
 	};
 
-	Manifest {
+	Ok(Manifest {
 		crate_info,
-		workspace_root: std::env::var("CARGO_MANIFEST_DIR").expect("CARGO_MANIFEST_DIR"),
+		workspace_root,
+	})
+}
+
+/// Locates the root of the workspace that contains the crate in `manifest_dir` the same way cargo does.
+///
+/// The `package.workspace` key takes precedence. Otherwise, the closest directory (starting with `manifest_dir` itself)
+/// whose manifest contains a `[workspace]` table is the root, unless that workspace excludes the crate. Crates that are
+/// not part of any workspace are their own root.
+fn workspace_root(
+	manifest_dir: &Path,
+	manifest: &toml::Value,
+	read_manifest: &dyn Fn(&Path) -> Option<toml::Value>,
+) -> PathBuf {
+	if manifest.get("workspace").is_some() {
+		return manifest_dir.to_path_buf();
+	}
+	if let Some(workspace) = manifest
+		.get("package")
+		.and_then(|package| package.get("workspace"))
+		.and_then(|workspace| workspace.as_str())
+	{
+		return normalize(&manifest_dir.join(workspace));
+	}
+
+	for dir in manifest_dir.ancestors().skip(1) {
+		if let Some(workspace) = read_manifest(&dir.join("Cargo.toml"))
+			.as_ref()
+			.and_then(|manifest| manifest.get("workspace"))
+		{
+			let excluded = workspace
+				.get("exclude")
+				.and_then(|exclude| exclude.as_array())
+				.into_iter()
+				.flatten()
+				.filter_map(|exclude| exclude.as_str())
+				.any(|exclude| manifest_dir.starts_with(normalize(&dir.join(exclude))));
+			return if excluded {
+				manifest_dir.to_path_buf()
+			} else {
+				dir.to_path_buf()
+			};
+		}
+	}
+
+	manifest_dir.to_path_buf()
+}
+
+/// Resolves `.` and `..` components without touching the file system
+fn normalize(path: &Path) -> PathBuf {
+	let mut normalized = PathBuf::new();
+	for component in path.components() {
+		match component {
+			Component::CurDir => (),
+			Component::ParentDir => {
+				normalized.pop();
+			}
+			component => normalized.push(component),
+		}
+	}
+	normalized
+}
+
+/// The path of `dir` relative to `root`, using `/` as separator
+fn relative_path(dir: &Path, root: &Path) -> Option<String> {
+	let relative = dir.strip_prefix(root).ok()?;
+	if relative.as_os_str().is_empty() {
+		return Some(".".to_string());
+	}
+	relative
+		.components()
+		.map(|component| component.as_os_str().to_str())
+		.collect::<Option<Vec<_>>>()
+		.map(|components| components.join("/"))
+}
+
+/// Used in place of the manifest information if it cannot be collected
+pub(crate) fn fallback() -> Manifest {
+	Manifest {
+		crate_info: CrateInfo {
+			name: std::env::var("CARGO_PKG_NAME").unwrap_or_else(|_| "UNKNOWN".to_string()),
+			version: std::env::var("CARGO_PKG_VERSION")
+				.ok()
+				.and_then(|version| version.parse().ok())
+				.unwrap_or_else(|| Version::new(0, 0, 0)),
+			git_version: None,
+			authors: Vec::new(),
+			license: None,
+			enabled_features: Vec::new(),
+			available_features: Vec::new(),
+			dependencies: Vec::new(),
+			workspace_root: None,
+			path_in_workspace: None,
+		},
+		workspace_root: std::env::var_os("CARGO_MANIFEST_DIR").map_or_else(|| PathBuf::from("."), PathBuf::from),
+	}
+}
+
+/// Collects all features declared in the `[features]` table of the manifest, as well as the implicit features that are
+/// created for optional dependencies that are not referred to via `dep:` syntax.
+fn available_features(manifest: &toml::Value) -> Vec<String> {
+	let mut features: Vec<String> = manifest
+		.get("features")
+		.and_then(|features| features.as_table())
+		.map(|features| features.keys().cloned().collect())
+		.unwrap_or_default();
+
+	let uses_dep_syntax = |name: &str| {
+		let reference = format!("dep:{name}");
+		manifest
+			.get("features")
+			.and_then(|features| features.as_table())
+			.into_iter()
+			.flat_map(|features| features.values())
+			.filter_map(|enables| enables.as_array())
+			.flatten()
+			.any(|enabled| enabled.as_str() == Some(&reference))
+	};
+
+	let dependency_tables = ["dependencies", "build-dependencies"]
+		.into_iter()
+		.filter_map(|key| manifest.get(key))
+		.chain(
+			manifest
+				.get("target")
+				.and_then(|targets| targets.as_table())
+				.into_iter()
+				.flat_map(|targets| targets.values())
+				.flat_map(|target| {
+					["dependencies", "build-dependencies"]
+						.into_iter()
+						.filter_map(|key| target.get(key))
+				}),
+		)
+		.filter_map(|table| table.as_table());
+	for dependencies in dependency_tables {
+		for (name, dependency) in dependencies {
+			let optional = dependency
+				.get("optional")
+				.and_then(|optional| optional.as_bool())
+				.unwrap_or(false);
+			if optional && !uses_dep_syntax(name) && !features.contains(name) {
+				features.push(name.clone());
+			}
+		}
+	}
+
+	features.sort();
+	features
+}
+
+/// Maps the feature names as seen in the `CARGO_FEATURE_*` environment variables back to their original spelling.
+///
+/// Cargo uppercases feature names and replaces `-` with `_` when passing them to the build script. Features that cannot
+/// be found among the available features are reported in lowercase.
+fn enabled_features(available_features: &[String], env_features: impl Iterator<Item = String>) -> Vec<String> {
+	let mut enabled_features: Vec<String> = env_features
+		.map(|env_feature| {
+			available_features
+				.iter()
+				.find(|feature| feature.to_ascii_uppercase().replace('-', "_") == env_feature)
+				.cloned()
+				.unwrap_or_else(|| env_feature.to_ascii_lowercase())
+		})
+		.collect();
+	enabled_features.sort();
+	enabled_features
+}
+
+/// Resolves the dependency graph of the current crate for the current target and feature set.
+///
+/// Only normal dependencies are collected; build- and dev-dependencies do not end up in the final artifact.
+pub(crate) fn read_dependencies(
+	enabled_features: &[String],
+	available_features: &[String],
+) -> Result<Vec<CrateInfo>, BuildInfoError> {
+	let cargo_toml = super::cargo_toml()?;
+	// Features that could not be mapped back to their declaration would be rejected by `cargo metadata`
+	let features = enabled_features
+		.iter()
+		.filter(|feature| available_features.contains(feature))
+		.cloned()
+		.collect();
+	let mut command = MetadataCommand::new();
+	command
+		.manifest_path(&cargo_toml)
+		.features(CargoOpt::NoDefaultFeatures)
+		.features(CargoOpt::SomeFeatures(features));
+	if let Ok(target) = std::env::var("TARGET") {
+		command.other_options(vec!["--filter-platform".to_string(), target]);
+	}
+	let metadata = command.exec().map_err(|err| BuildInfoError::Dependencies(err.into()))?;
+	dependency_tree(&metadata, &cargo_toml)
+}
+
+/// Builds the dependency tree of the crate with the manifest `cargo_toml` from the output of `cargo metadata`
+fn dependency_tree(metadata: &Metadata, cargo_toml: &Path) -> Result<Vec<CrateInfo>, BuildInfoError> {
+	let resolve = metadata.resolve.as_ref().ok_or_else(|| {
+		BuildInfoError::Dependencies(anyhow::anyhow!(
+			"`cargo metadata` did not return a resolved dependency graph"
+		))
+	})?;
+
+	let root = resolve
+		.root
+		.as_ref()
+		.or_else(|| {
+			metadata
+				.packages
+				.iter()
+				.find(|package| package.manifest_path.as_std_path() == cargo_toml)
+				.map(|package| &package.id)
+		})
+		.ok_or_else(|| {
+			BuildInfoError::Dependencies(anyhow::anyhow!(
+				"Could not find the current crate in the output of `cargo metadata`"
+			))
+		})?;
+
+	let mut graph = DependencyGraph {
+		packages: metadata.packages.iter().map(|package| (&package.id, package)).collect(),
+		nodes: resolve.nodes.iter().map(|node| (&node.id, node)).collect(),
+		dependencies: HashMap::new(),
+	};
+	graph.dependencies_of(root)
+}
+
+/// The resolved dependency graph, which remembers the dependencies of each package, as crates that are shared by
+/// multiple dependents would otherwise be visited once per path that leads to them
+struct DependencyGraph<'a> {
+	packages: HashMap<&'a PackageId, &'a Package>,
+	nodes: HashMap<&'a PackageId, &'a Node>,
+	dependencies: HashMap<&'a PackageId, Vec<CrateInfo>>,
+}
+
+impl<'a> DependencyGraph<'a> {
+	fn node(&self, id: &PackageId) -> Result<&'a Node, BuildInfoError> {
+		self.nodes.get(id).copied().ok_or_else(|| {
+			BuildInfoError::Dependencies(anyhow::anyhow!(
+				"`cargo metadata` did not resolve the dependencies of {}",
+				id
+			))
+		})
+	}
+
+	fn dependencies_of(&mut self, id: &'a PackageId) -> Result<Vec<CrateInfo>, BuildInfoError> {
+		if let Some(dependencies) = self.dependencies.get(id) {
+			return Ok(dependencies.clone());
+		}
+
+		let mut dependencies = Vec::new();
+		for dep in &self.node(id)?.deps {
+			// `dep_kinds` is only available since Rust 1.41; older versions of cargo only report normal dependencies
+			if dep.dep_kinds.is_empty() || dep.dep_kinds.iter().any(|info| info.kind == DependencyKind::Normal) {
+				dependencies.push(self.dependency_info(&dep.pkg)?);
+			}
+		}
+		dependencies.sort();
+		self.dependencies.insert(id, dependencies.clone());
+		Ok(dependencies)
+	}
+
+	fn dependency_info(&mut self, id: &'a PackageId) -> Result<CrateInfo, BuildInfoError> {
+		let package = self.packages.get(id).copied().ok_or_else(|| {
+			BuildInfoError::Dependencies(anyhow::anyhow!("`cargo metadata` did not describe the package {}", id))
+		})?;
+
+		let mut available_features: Vec<String> = package.features.keys().cloned().collect();
+		available_features.sort();
+
+		Ok(CrateInfo {
+			name: package.name.clone(),
+			version: package.version.clone(),
+			git_version: None,
+			authors: package.authors.clone(),
+			license: package.license.clone(),
+			enabled_features: self.node(id)?.features.clone(),
+			available_features,
+			dependencies: self.dependencies_of(id)?,
+			workspace_root: None,
+			path_in_workspace: None,
+		})
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use pretty_assertions::assert_eq;
+
+	use super::*;
+
+	#[test]
+	fn features_from_manifest() {
+		let manifest: toml::Value = r#"
+			[package]
+			name = "test"
+
+			[dependencies]
+			serde = { version = "1", optional = true }
+			serde_json = { version = "1", optional = true }
+			chrono = "0.4"
+
+			[target.'cfg(unix)'.dependencies]
+			libc = { version = "0.2", optional = true }
+
+			[features]
+			default = ["my-feature"]
+			my-feature = []
+			json = ["dep:serde_json"]
+		"#
+		.parse()
+		.unwrap();
+
+		assert_eq!(
+			available_features(&manifest),
+			vec!["default", "json", "libc", "my-feature", "serde"]
+		);
+	}
+
+	#[test]
+	fn enabled_features_keep_original_spelling() {
+		let available = vec!["default".to_string(), "my-feature".to_string(), "serde".to_string()];
+		let env_features = vec![
+			"MY_FEATURE".to_string(),
+			"DEFAULT".to_string(),
+			"UNKNOWN_FEATURE".to_string(),
+		];
+
+		assert_eq!(
+			enabled_features(&available, env_features.into_iter()),
+			vec!["default", "my-feature", "unknown_feature"]
+		);
+	}
+
+	fn find_root(manifest_dir: &str, manifests: &[(&str, &str)]) -> PathBuf {
+		let manifests: HashMap<PathBuf, toml::Value> = manifests
+			.iter()
+			.map(|(dir, manifest)| (Path::new(dir).join("Cargo.toml"), manifest.parse().unwrap()))
+			.collect();
+		let manifest = &manifests[&Path::new(manifest_dir).join("Cargo.toml")];
+		workspace_root(Path::new(manifest_dir), manifest, &|path| manifests.get(path).cloned())
+	}
+
+	#[test]
+	fn workspace_member() {
+		let manifests = [
+			("/ws", "[workspace]\nmembers = [\"crates/*\"]"),
+			("/ws/crates/a", "[package]\nname = \"a\""),
+		];
+		assert_eq!(find_root("/ws/crates/a", &manifests), Path::new("/ws"));
+		assert_eq!(
+			relative_path(Path::new("/ws/crates/a"), Path::new("/ws")).as_deref(),
+			Some("crates/a")
+		);
+		assert_eq!(relative_path(Path::new("/ws"), Path::new("/ws")).as_deref(), Some("."));
+	}
+
+	#[test]
+	fn workspace_root_itself_and_standalone() {
+		let manifests = [
+			("/ws", "[workspace]\n[package]\nname = \"root\""),
+			("/other/a", "[package]\nname = \"a\""),
+		];
+		assert_eq!(find_root("/ws", &manifests), Path::new("/ws"));
+		assert_eq!(find_root("/other/a", &manifests), Path::new("/other/a"));
+	}
+
+	#[test]
+	fn explicit_and_excluded_workspaces() {
+		let manifests = [
+			("/ws", "[workspace]\nexclude = [\"tools/\"]"),
+			("/ws/tools/a", "[package]\nname = \"a\""),
+			("/elsewhere/b", "[package]\nname = \"b\"\nworkspace = \"../../ws\""),
+		];
+		assert_eq!(find_root("/ws/tools/a", &manifests), Path::new("/ws/tools/a"));
+		assert_eq!(find_root("/elsewhere/b", &manifests), Path::new("/ws"));
+	}
+
+	/// `cargo metadata` output for `root`, whose dependencies `a` and `b` both depend on `c`
+	fn diamond() -> serde_json::Value {
+		let package = |name: &str| {
+			serde_json::json!({
+				"name": name,
+				"version": "1.0.0",
+				"id": name,
+				"dependencies": [],
+				"targets": [],
+				"features": {},
+				"manifest_path": format!("/ws/{}/Cargo.toml", name),
+			})
+		};
+		let node = |name: &str, deps: &[&str]| {
+			let deps: Vec<_> = deps
+				.iter()
+				.map(|dep| serde_json::json!({"name": dep, "pkg": dep, "dep_kinds": [{"kind": null, "target": null}]}))
+				.collect();
+			serde_json::json!({"id": name, "deps": deps, "dependencies": [], "features": []})
+		};
+		serde_json::json!({
+			"packages": [package("root"), package("a"), package("b"), package("c")],
+			"workspace_members": ["root"],
+			"resolve": {
+				"root": "root",
+				"nodes": [node("root", &["a", "b"]), node("a", &["c"]), node("b", &["c"]), node("c", &[])],
+			},
+			"workspace_root": "/ws",
+			"target_directory": "/ws/target",
+			"version": 1,
+		})
+	}
+
+	#[test]
+	fn shared_dependencies() {
+		let metadata: Metadata = serde_json::from_value(diamond()).unwrap();
+		let dependencies = dependency_tree(&metadata, Path::new("/ws/root/Cargo.toml")).unwrap();
+		assert_eq!(
+			dependencies.iter().map(|dep| dep.name.as_str()).collect::<Vec<_>>(),
+			["a", "b"]
+		);
+		for dependency in &dependencies {
+			assert_eq!(dependency.dependencies.len(), 1);
+			assert_eq!(dependency.dependencies[0].name, "c");
+			assert_eq!(dependency.dependencies[0].version, Version::new(1, 0, 0));
+		}
+	}
+
+	#[test]
+	fn partial_resolve() {
+		let mut metadata = diamond();
+		metadata["resolve"]["nodes"].as_array_mut().unwrap().pop();
+		let metadata: Metadata = serde_json::from_value(metadata).unwrap();
+		assert!(matches!(
+			dependency_tree(&metadata, Path::new("/ws/root/Cargo.toml")),
+			Err(BuildInfoError::Dependencies(_))
+		));
 	}
 }
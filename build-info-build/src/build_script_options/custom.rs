@@ -0,0 +1,13 @@
+use build_info_common::CustomValue;
+
+impl crate::BuildScriptOptions {
+	/// Attaches a user-defined value to the generated `BuildInfo`.
+	///
+	/// The value is available at runtime as `build_info().custom[key]` and inside `build_info::format!` as
+	/// `$.custom.key`. Setting the same key twice replaces the previous value. Nested maps can be created from a
+	/// `BTreeMap` or by collecting an iterator of key/value pairs into a `CustomValue`.
+	pub fn custom(mut self, key: impl Into<String>, value: impl Into<CustomValue>) -> Self {
+		self.custom.insert(key.into(), value.into());
+		self
+	}
+}
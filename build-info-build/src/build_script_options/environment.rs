@@ -0,0 +1,34 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::BuildInfoError;
+
+impl crate::BuildScriptOptions {
+	/// Records the values of the given environment variables in `BuildInfo::environment`.
+	///
+	/// Variables that are not set at build time are recorded as `None`. Since cargo is asked to rerun the build script
+	/// whenever one of these variables changes, the recorded values never go stale. Variables whose values are not valid
+	/// UTF-8 are converted lossily.
+	pub fn capture_env<I, S>(mut self, names: I) -> Self
+	where
+		I: IntoIterator<Item = S>,
+		S: Into<String>,
+	{
+		self.capture_env.extend(names.into_iter().map(Into::into));
+		self
+	}
+}
+
+pub(crate) fn get_info(names: &BTreeSet<String>) -> Result<BTreeMap<String, Option<String>>, BuildInfoError> {
+	names
+		.iter()
+		.map(|name| {
+			// Looking up such a name would panic
+			if name.is_empty() || name.contains(['=', '\0']) {
+				return Err(BuildInfoError::InvalidEnvVarName { name: name.clone() });
+			}
+			println!("cargo:rerun-if-env-changed={}", name);
+			let value = std::env::var_os(name).map(|value| value.to_string_lossy().into_owned());
+			Ok((name.clone(), value))
+		})
+		.collect()
+}
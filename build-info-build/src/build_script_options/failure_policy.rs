@@ -0,0 +1,117 @@
+use crate::BuildInfoError;
+
+/// The individual steps that collect the build information
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[non_exhaustive]
+pub enum Collector {
+	/// The build timestamp, which may be taken from `SOURCE_DATE_EPOCH`
+	Timestamp,
+	/// The optimization level, taken from `OPT_LEVEL`
+	OptimizationLevel,
+	/// Information about the crate, taken from the manifest and the `CARGO_PKG_*` variables
+	CrateInfo,
+	/// The dependency graph, if enabled via `collect_dependencies(true)`
+	Dependencies,
+	/// Information about the compiler and the target platform
+	Compiler,
+	/// The name and the settings of the profile, which are partly taken from the workspace manifest
+	Profile,
+	/// Information about the version control system
+	VersionControl,
+	/// The environment variables requested via `capture_env`
+	Environment,
+	/// The `cargo:rerun-if-changed` lines for the files of the project
+	RerunTriggers,
+}
+
+/// Determines what happens when a collector fails
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum FailurePolicy {
+	/// Abort with an error
+	Fail,
+	/// Emit a `cargo:warning` and continue with a default value (or no value at all, for optional data)
+	Warn,
+	/// Silently continue with a default value (or no value at all, for optional data)
+	UseDefault,
+}
+
+impl FailurePolicy {
+	/// The policy that is used if no other policy was set for the collector.
+	///
+	/// Version control information is optional, so failing to collect it is not an error. All other collectors fail.
+	pub(crate) fn default_for(collector: Collector) -> Self {
+		match collector {
+			Collector::VersionControl => FailurePolicy::UseDefault,
+			_ => FailurePolicy::Fail,
+		}
+	}
+
+	/// Applies the policy to the result of a collector, using `fallback` to produce the default value.
+	pub(crate) fn apply<T>(
+		self,
+		result: Result<T, BuildInfoError>,
+		fallback: impl FnOnce() -> T,
+	) -> Result<T, BuildInfoError> {
+		match (result, self) {
+			(Ok(value), _) => Ok(value),
+			(Err(err), FailurePolicy::Fail) => Err(err),
+			(Err(err), FailurePolicy::Warn) => {
+				println!("cargo:warning={}", err.to_string().replace('\n', " "));
+				Ok(fallback())
+			}
+			(Err(_), FailurePolicy::UseDefault) => Ok(fallback()),
+		}
+	}
+}
+
+impl crate::BuildScriptOptions {
+	/// Sets how a failure of the given collector is handled.
+	///
+	/// By default, failing to collect version control information is silently ignored, while all other failures abort
+	/// the build script. Use this to keep going when, e.g., `cargo metadata` cannot resolve the dependencies in an
+	/// unusual build environment:
+	///
+	/// ```rust,no_run
+	/// use build_info_build::{Collector, FailurePolicy};
+	///
+	/// build_info_build::build_script()
+	/// 	.collect_dependencies(true)
+	/// 	.on_failure(Collector::Dependencies, FailurePolicy::Warn);
+	/// ```
+	///
+	/// The compiler information has no meaningful default value, so it is omitted (`BuildInfo::compiler` is `None`) if
+	/// `Collector::Compiler` fails under a policy other than `FailurePolicy::Fail`.
+	pub fn on_failure(mut self, collector: Collector, policy: FailurePolicy) -> Self {
+		self.failure_policies.insert(collector, policy);
+		self
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use pretty_assertions::assert_eq;
+
+	use super::*;
+
+	fn failure() -> Result<u32, BuildInfoError> {
+		Err(BuildInfoError::MissingEnvVar {
+			name: "TEST".to_string(),
+		})
+	}
+
+	#[test]
+	fn success_ignores_policy() {
+		assert_eq!(FailurePolicy::Fail.apply(Ok(1), || 0).unwrap(), 1);
+		assert_eq!(FailurePolicy::UseDefault.apply(Ok(1), || 0).unwrap(), 1);
+	}
+
+	#[test]
+	fn failure_uses_policy() {
+		assert!(matches!(
+			FailurePolicy::Fail.apply(failure(), || 0),
+			Err(BuildInfoError::MissingEnvVar { .. })
+		));
+		assert_eq!(FailurePolicy::Warn.apply(failure(), || 2).unwrap(), 2);
+		assert_eq!(FailurePolicy::UseDefault.apply(failure(), || 3).unwrap(), 3);
+	}
+}
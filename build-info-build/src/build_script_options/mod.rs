@@ -1,23 +1,47 @@
 use core::sync::atomic::{AtomicBool, Ordering};
-use std::path::{Path, PathBuf};
+use std::{
+	collections::{BTreeMap, BTreeSet},
+	path::{Path, PathBuf},
+};
 
 use base64::write::EncoderWriter as Base64Encoder;
-use build_info_common::{OptimizationLevel, VersionedString};
+use build_info_common::{CustomValue, OptimizationLevel, VersionedString};
 use xz2::write::XzEncoder;
 
 use super::{
 	chrono::{DateTime, Utc},
-	BuildInfo,
+	BuildInfo, BuildInfoError,
 };
 
 mod compiler;
 mod crate_info;
+mod custom;
+mod environment;
+mod failure_policy;
+mod profile;
+mod rerun;
+mod tag_version;
 mod timestamp;
+mod toolchain;
 mod version_control;
 
-lazy_static::lazy_static! {
-	static ref CARGO_TOML: PathBuf = Path::new(&std::env::var_os("CARGO_MANIFEST_DIR").unwrap())
-		.join("Cargo.toml");
+pub use failure_policy::{Collector, FailurePolicy};
+
+/// The path of the manifest of the crate that is being built
+fn cargo_toml() -> Result<PathBuf, BuildInfoError> {
+	let manifest_dir = std::env::var_os("CARGO_MANIFEST_DIR").ok_or_else(|| BuildInfoError::MissingEnvVar {
+		name: "CARGO_MANIFEST_DIR".to_string(),
+	})?;
+	Ok(Path::new(&manifest_dir).join("Cargo.toml"))
+}
+
+/// Emits a `cargo:rerun-if-changed` line for `path`, which cargo can only accept if the path is valid UTF-8.
+fn rerun_if_changed(path: &Path) -> Result<(), BuildInfoError> {
+	let path = path
+		.to_str()
+		.ok_or_else(|| BuildInfoError::NonUtf8Path { path: path.to_owned() })?;
+	println!("cargo:rerun-if-changed={}", path);
+	Ok(())
 }
 
 /// Type to store any (optional) options for the build script.
@@ -30,73 +54,184 @@ pub struct BuildScriptOptions {
 
 	/// Enable dependency collection
 	collect_dependencies: bool,
+
+	/// Derive a version number from the nearest git tag
+	version_from_git_tags: bool,
+
+	/// User-defined data to be included in the `BuildInfo`
+	custom: BTreeMap<String, CustomValue>,
+
+	/// Names of the environment variables to capture
+	capture_env: BTreeSet<String>,
+
+	/// Files that cause the build script to be rerun
+	rerun: rerun::RerunOptions,
+
+	/// How version control information is collected
+	version_control: version_control::VersionControlOptions,
+
+	/// How failures of the individual collectors are handled
+	failure_policies: BTreeMap<Collector, FailurePolicy>,
+
+	/// Stores if another `BuildScriptOptions` has been created before this one
+	already_run: bool,
 }
 static BUILD_SCRIPT_RAN: AtomicBool = AtomicBool::new(false);
 
 impl BuildScriptOptions {
+	fn collect<T>(
+		&self,
+		collector: Collector,
+		result: Result<T, BuildInfoError>,
+		fallback: impl FnOnce() -> T,
+	) -> Result<T, BuildInfoError> {
+		self
+			.failure_policies
+			.get(&collector)
+			.copied()
+			.unwrap_or_else(|| FailurePolicy::default_for(collector))
+			.apply(result, fallback)
+	}
+
 	/// WARNING: Should only be called once!
-	fn drop_to_build_info(&mut self) -> BuildInfo {
+	fn drop_to_build_info(&mut self) -> Result<BuildInfo, BuildInfoError> {
 		assert!(!self.consumed);
 		self.consumed = true;
 
-		let profile = std::env::var("PROFILE").unwrap_or_else(|_| "UNKNOWN".to_string());
-		let optimization_level = match std::env::var("OPT_LEVEL")
-			.expect("Expected environment variable `OPT_LEVEL` to be set by cargo")
-			.as_str()
-		{
-			"0" => OptimizationLevel::O0,
-			"1" => OptimizationLevel::O1,
-			"2" => OptimizationLevel::O2,
-			"3" => OptimizationLevel::O3,
-			"s" => OptimizationLevel::Os,
-			"z" => OptimizationLevel::Oz,
-			level => panic!("Unknown optimization level {level:?}"),
-		};
+		if self.already_run {
+			return Err(BuildInfoError::AlreadyRun);
+		}
+		self.version_control.check()?;
 
-		let compiler = compiler::get_info();
+		let optimization_level = self.collect(
+			Collector::OptimizationLevel,
+			optimization_level(&|name| std::env::var(name).ok()),
+			|| OptimizationLevel::O0,
+		)?;
+		let compiler = self.collect(Collector::Compiler, compiler::get_info().map(Some), || None)?;
 		let crate_info::Manifest {
-			crate_info,
+			mut crate_info,
 			workspace_root,
-		} = crate_info::read_manifest();
-		let version_control = version_control::get_info();
+		} = self.collect(Collector::CrateInfo, crate_info::read_manifest(), crate_info::fallback)?;
+		if self.collect_dependencies {
+			crate_info.dependencies = self.collect(
+				Collector::Dependencies,
+				crate_info::read_dependencies(&crate_info.enabled_features, &crate_info.available_features),
+				Vec::new,
+			)?;
+		}
+		let (profile, profile_settings) = self.collect(
+			Collector::Profile,
+			profile::get_info(&workspace_root),
+			profile::fallback,
+		)?;
+		let version_control = self.collect(
+			Collector::VersionControl,
+			version_control::get_info(&self.version_control),
+			|| None,
+		)?;
+		if self.version_from_git_tags {
+			let git = version_control
+				.as_ref()
+				.and_then(|version_control| version_control.git());
+			crate_info.git_version = git.and_then(tag_version::get_version);
+			if crate_info.git_version.is_none() {
+				match git.and_then(|git| git.nearest_tag.as_ref()) {
+					Some(tag) => println!(
+						"cargo:warning=Could not derive a version number, as the tag {tag:?} is not a semantic version, and \
+						 neither is any older tag"
+					),
+					None => println!("cargo:warning=Could not derive a version number, as no tag was found"),
+				}
+			}
+		}
+		let environment = self.collect(
+			Collector::Environment,
+			environment::get_info(&self.capture_env),
+			BTreeMap::new,
+		)?;
 
-		let timestamp = self.timestamp.unwrap_or_else(timestamp::get_timestamp);
+		let timestamp = match self.timestamp {
+			Some(timestamp) => timestamp,
+			None => self.collect(Collector::Timestamp, timestamp::get_timestamp(), Utc::now)?,
+		};
 		let build_info = BuildInfo {
 			timestamp,
 			profile,
 			optimization_level,
+			profile_settings,
 			crate_info,
 			compiler,
 			version_control,
+			custom: std::mem::take(&mut self.custom),
+			environment,
 		};
 
-		let mut bytes = Vec::new();
-		let string_safe = Base64Encoder::new(&mut bytes, base64::STANDARD_NO_PAD);
-		let mut compressed = XzEncoder::new(string_safe, 9);
-		bincode::serialize_into(&mut compressed, &build_info).unwrap();
-		compressed.finish().unwrap().finish().unwrap();
-
-		let string = String::from_utf8(bytes).unwrap();
-		let versioned = VersionedString::build_info_common_versioned(string);
-		let serialized = serde_json::to_string(&versioned).unwrap();
-
-		println!("cargo:rustc-env=BUILD_INFO={}", serialized);
+		let serialized = serialize(&build_info).map_err(BuildInfoError::Serialization)?;
 
 		// Whenever any `cargo:rerun-if-changed` key is set, the default set is cleared.
 		// Since we will need to emit such keys to trigger rebuilds when the vcs repository changes state,
 		// we also have to emit the customary triggers again, or we will only be rerun in that exact case.
-		rebuild_if_project_changes(&workspace_root);
+		self.collect(
+			Collector::RerunTriggers,
+			rebuild_if_project_changes(&workspace_root, &self.rerun),
+			|| (),
+		)?;
 
-		build_info
+		println!("cargo:rustc-env=BUILD_INFO={}", serialized);
+
+		Ok(build_info)
 	}
 
 	/// Consumes the `BuildScriptOptions` and returns a `BuildInfo` object. Use this function if you wish to inspect the
 	/// generated build information in `build.rs`.
+	///
+	/// Panics if the build information cannot be collected; see [`try_build`](Self::try_build) for a non-panicking
+	/// alternative.
 	pub fn build(mut self) -> BuildInfo {
+		self.drop_to_build_info().unwrap_or_else(|err| panic!("{}", err))
+	}
+
+	/// Consumes the `BuildScriptOptions` and returns a `BuildInfo` object, or the error that prevented its collection.
+	///
+	/// How failures of the individual collectors are handled can be configured via
+	/// [`on_failure`](Self::on_failure). If an error is returned, no build information is made available to the
+	/// `build_info` crate.
+	pub fn try_build(mut self) -> Result<BuildInfo, BuildInfoError> {
 		self.drop_to_build_info()
 	}
 }
 
+fn optimization_level(env: &dyn Fn(&str) -> Option<String>) -> Result<OptimizationLevel, BuildInfoError> {
+	let level = env("OPT_LEVEL").ok_or_else(|| BuildInfoError::MissingEnvVar {
+		name: "OPT_LEVEL".to_string(),
+	})?;
+	match level.as_str() {
+		"0" => Ok(OptimizationLevel::O0),
+		"1" => Ok(OptimizationLevel::O1),
+		"2" => Ok(OptimizationLevel::O2),
+		"3" => Ok(OptimizationLevel::O3),
+		"s" => Ok(OptimizationLevel::Os),
+		"z" => Ok(OptimizationLevel::Oz),
+		level => Err(BuildInfoError::InvalidEnvVar {
+			name: "OPT_LEVEL".to_string(),
+			value: level.to_string(),
+		}),
+	}
+}
+
+fn serialize(build_info: &BuildInfo) -> anyhow::Result<String> {
+	let mut bytes = Vec::new();
+	let string_safe = Base64Encoder::new(&mut bytes, base64::STANDARD_NO_PAD);
+	let mut compressed = XzEncoder::new(string_safe, 9);
+	bincode::serialize_into(&mut compressed, build_info)?;
+	compressed.finish()?.finish()?;
+
+	let string = String::from_utf8(bytes)?;
+	let versioned = VersionedString::build_info_common_versioned(string);
+	Ok(serde_json::to_string(&versioned)?)
+}
+
 impl From<BuildScriptOptions> for BuildInfo {
 	fn from(opts: BuildScriptOptions) -> BuildInfo {
 		opts.build()
@@ -105,13 +240,19 @@ impl From<BuildScriptOptions> for BuildInfo {
 
 impl Default for BuildScriptOptions {
 	fn default() -> Self {
-		let build_script_ran = BUILD_SCRIPT_RAN.swap(true, Ordering::Relaxed);
-		assert!(!build_script_ran, "The build script may only be run once.");
+		let already_run = BUILD_SCRIPT_RAN.swap(true, Ordering::Relaxed);
 
 		Self {
 			consumed: false,
 			timestamp: None,
 			collect_dependencies: false,
+			version_from_git_tags: false,
+			custom: BTreeMap::new(),
+			capture_env: BTreeSet::new(),
+			rerun: Default::default(),
+			version_control: Default::default(),
+			failure_policies: BTreeMap::new(),
+			already_run,
 		}
 	}
 }
@@ -119,7 +260,9 @@ impl Default for BuildScriptOptions {
 impl Drop for BuildScriptOptions {
 	fn drop(&mut self) {
 		if !self.consumed {
-			let _build_info = self.drop_to_build_info();
+			if let Err(err) = self.drop_to_build_info() {
+				panic!("{}", err);
+			}
 		}
 	}
 }
@@ -127,26 +270,81 @@ impl Drop for BuildScriptOptions {
 /// Emits a `cargo:rerun-if-changed` line for each file in the target project.
 /// By default, the following files are included:
 /// - `Cargo.toml`
+/// - `$workspace_root/Cargo.toml`, which may contain the profile settings
 /// - `$workspace_root/Cargo.lock`
-/// - Any file that ends in `.rs`
-fn rebuild_if_project_changes(workspace_root: &str) {
-	println!("cargo:rerun-if-changed={}", CARGO_TOML.to_str().unwrap());
-	println!(
-		"cargo:rerun-if-changed={}",
-		Path::new(workspace_root).join("Cargo.lock").to_str().unwrap()
-	);
-
-	for source in glob::glob_with(
-		"**/*.rs",
-		glob::MatchOptions {
-			case_sensitive: false,
-			require_literal_separator: false,
-			require_literal_leading_dot: false,
-		},
-	)
-	.unwrap()
-	.map(|source| source.unwrap())
-	{
-		println!("cargo:rerun-if-changed={}", source.to_str().unwrap());
+/// - Any file in the crate directory that matches the `RerunOptions` (by default, any file that ends in `.rs`)
+fn rebuild_if_project_changes(workspace_root: &Path, options: &rerun::RerunOptions) -> Result<(), BuildInfoError> {
+	let cargo_toml = cargo_toml()?;
+	rerun_if_changed(&cargo_toml)?;
+	if workspace_root.join("Cargo.toml") != cargo_toml {
+		rerun_if_changed(&workspace_root.join("Cargo.toml"))?;
+	}
+	rerun_if_changed(&workspace_root.join("Cargo.lock"))?;
+
+	for file in rerun::project_files(cargo_toml.parent().unwrap_or_else(|| Path::new(".")), options)? {
+		rerun_if_changed(&file)?;
+	}
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod test {
+	use pretty_assertions::assert_eq;
+
+	use super::*;
+
+	/// Creates options that are not affected by other tests creating options in the same process, and that are not built
+	/// when they are dropped
+	fn options() -> BuildScriptOptions {
+		let mut options = BuildScriptOptions::default();
+		options.already_run = false;
+		options.consumed = true;
+		options
+	}
+
+	fn failure<T>() -> Result<T, BuildInfoError> {
+		Err(BuildInfoError::MissingEnvVar {
+			name: "TEST".to_string(),
+		})
+	}
+
+	#[test]
+	fn optimization_levels() {
+		let level = |value: Option<&str>| optimization_level(&|_| value.map(|value| value.to_string()));
+		assert_eq!(level(Some("3")).unwrap(), OptimizationLevel::O3);
+		assert_eq!(level(Some("z")).unwrap(), OptimizationLevel::Oz);
+		assert!(matches!(
+			level(Some("fast")),
+			Err(BuildInfoError::InvalidEnvVar { name, .. }) if name == "OPT_LEVEL"
+		));
+		assert!(matches!(level(None), Err(BuildInfoError::MissingEnvVar { .. })));
+	}
+
+	#[test]
+	fn collectors_use_their_policy() {
+		let options = options();
+		assert!(options.collect(Collector::OptimizationLevel, failure(), || 0).is_err());
+		assert!(options.collect(Collector::Compiler, failure(), || 0).is_err());
+		assert_eq!(options.collect(Collector::VersionControl, failure(), || 1).unwrap(), 1);
+
+		let options = options
+			.on_failure(Collector::Compiler, FailurePolicy::UseDefault)
+			.on_failure(Collector::VersionControl, FailurePolicy::Fail);
+		assert_eq!(
+			options
+				.collect(Collector::Compiler, failure::<Option<u32>>(), || None)
+				.unwrap(),
+			None
+		);
+		assert!(options.collect(Collector::VersionControl, failure(), || 1).is_err());
+	}
+
+	#[test]
+	fn invalid_crate_tag_pattern() {
+		assert!(matches!(
+			options().crate_tag_pattern("{crate}").version_control.check(),
+			Err(BuildInfoError::InvalidCrateTagPattern { pattern }) if pattern == "{crate}"
+		));
 	}
 }
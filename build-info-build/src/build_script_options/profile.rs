@@ -0,0 +1,298 @@
+use std::path::{Path, PathBuf};
+
+use build_info_common::{PanicStrategy, ProfileSettings};
+
+use crate::BuildInfoError;
+
+/// Determines the name of the current profile and its effective settings.
+///
+/// Cargo only tells build scripts whether they are part of a `"debug"` or a `"release"` build, so the name of the
+/// profile is taken from the layout of `OUT_DIR` instead. `debug_assertions` and `panic` are taken from the
+/// `CARGO_CFG_*` variables, which reflect the settings the crate is actually compiled with, including those made in
+/// `.cargo/config.toml` or via `RUSTFLAGS`. The other settings are resolved from the `CARGO_PROFILE_*` environment
+/// overrides and the `[profile.*]` tables of the workspace manifest, following `inherits`, and fall back to the values
+/// cargo passes to build scripts. Other profile settings made in `.cargo/config.toml` are not visible to build scripts
+/// and are therefore only taken into account if they are also visible in one of the other sources.
+pub(crate) fn get_info(workspace_root: &Path) -> Result<(String, ProfileSettings), BuildInfoError> {
+	let path = workspace_root.join("Cargo.toml");
+	let manifest = match std::fs::read_to_string(&path) {
+		Ok(manifest) => Some(
+			manifest
+				.parse::<toml::Value>()
+				.map_err(|err| BuildInfoError::Manifest(anyhow::Error::new(err).context(path.display().to_string())))?,
+		),
+		Err(err) if err.kind() == std::io::ErrorKind::NotFound => None,
+		Err(err) => {
+			return Err(BuildInfoError::Manifest(
+				anyhow::Error::new(err).context(path.display().to_string()),
+			))
+		}
+	};
+	Ok(with_manifest(manifest.as_ref()))
+}
+
+/// The profile as far as it can be determined without the workspace manifest
+pub(crate) fn fallback() -> (String, ProfileSettings) {
+	with_manifest(None)
+}
+
+fn with_manifest(manifest: Option<&toml::Value>) -> (String, ProfileSettings) {
+	let profile = profile_dir().unwrap_or_else(|| std::env::var("PROFILE").unwrap_or_else(|_| "UNKNOWN".to_string()));
+	let settings = resolve(&profile, manifest, &|name| std::env::var(name).ok());
+	(profile, settings)
+}
+
+/// `OUT_DIR` has the form `$target_dir/[$target_triple/]$profile_dir/build/$crate-$hash/out`.
+fn profile_dir() -> Option<String> {
+	let out_dir = PathBuf::from(std::env::var_os("OUT_DIR")?);
+	let mut ancestors = out_dir.ancestors().skip(2);
+	if ancestors.next()?.file_name()? != "build" {
+		return None;
+	}
+	ancestors.next()?.file_name()?.to_str().map(|name| name.to_string())
+}
+
+fn resolve(profile_dir: &str, manifest: Option<&toml::Value>, env: &dyn Fn(&str) -> Option<String>) -> ProfileSettings {
+	let profile = Profile::new(profile_dir, manifest, env);
+
+	// Cargo sets the `CARGO_CFG_*` variables of all active cfgs, so `CARGO_CFG_DEBUG_ASSERTIONS` is only missing if debug
+	// assertions are disabled; the manifest is only consulted if the build script does not run under cargo
+	let debug_assertions = if env("CARGO_CFG_TARGET_ARCH").is_some() {
+		env("CARGO_CFG_DEBUG_ASSERTIONS").is_some()
+	} else {
+		profile
+			.get("debug-assertions")
+			.and_then(|value| parse_bool(&value))
+			.unwrap_or(profile.is_dev())
+	};
+	let overflow_checks = profile
+		.get("overflow-checks")
+		.and_then(|value| parse_bool(&value))
+		.unwrap_or(profile.is_dev());
+	// `cfg(panic)` is only available since Rust 1.60
+	let panic = match env("CARGO_CFG_PANIC").or_else(|| profile.get("panic")).as_deref() {
+		Some("abort") => PanicStrategy::Abort,
+		_ => PanicStrategy::Unwind,
+	};
+	let lto = match profile.get("lto").as_deref() {
+		None | Some("false") => "false".to_string(),
+		Some("true") | Some("fat") => "fat".to_string(),
+		Some(lto) => lto.to_string(),
+	};
+	let incremental = env("CARGO_INCREMENTAL")
+		.map(|incremental| incremental == "1")
+		.or_else(|| profile.get("incremental").and_then(|value| parse_bool(&value)))
+		.unwrap_or(profile.is_dev());
+	let codegen_units = profile
+		.get("codegen-units")
+		.and_then(|value| value.parse().ok())
+		.unwrap_or(if incremental { 256 } else { 16 });
+	let debuginfo = profile
+		.get("debug")
+		.or_else(|| env("DEBUG"))
+		.map(|debug| normalize_debuginfo(&debug))
+		.unwrap_or_else(|| if profile.is_dev() { "full" } else { "none" }.to_string());
+
+	ProfileSettings {
+		debug_assertions,
+		overflow_checks,
+		panic,
+		lto,
+		codegen_units,
+		debuginfo,
+	}
+}
+
+/// A profile with its chain of inherited profiles, starting with the profile itself.
+struct Profile<'a> {
+	chain: Vec<String>,
+	manifest: Option<&'a toml::Value>,
+	env: &'a dyn Fn(&str) -> Option<String>,
+}
+
+impl<'a> Profile<'a> {
+	fn new(profile_dir: &str, manifest: Option<&'a toml::Value>, env: &'a dyn Fn(&str) -> Option<String>) -> Self {
+		let mut profile = Profile {
+			chain: Vec::new(),
+			manifest,
+			env,
+		};
+
+		// The `dev` profile uses the `debug` directory, all other profiles use their name
+		let mut name = if profile_dir == "debug" {
+			"dev".to_string()
+		} else {
+			profile_dir.to_string()
+		};
+		loop {
+			profile.chain.push(name.clone());
+			let parent = match name.as_str() {
+				"dev" | "release" => None,
+				"test" => Some("dev".to_string()),
+				"bench" => Some("release".to_string()),
+				_ => profile.get_direct(&name, "inherits"),
+			};
+			match parent {
+				Some(parent) if !profile.chain.contains(&parent) => name = parent,
+				_ => break,
+			}
+		}
+
+		profile
+	}
+
+	/// Whether the root of the inheritance chain is the `dev` profile
+	fn is_dev(&self) -> bool {
+		self.chain.last().map(|name| name == "dev").unwrap_or(false)
+	}
+
+	fn get(&self, key: &str) -> Option<String> {
+		self.chain.iter().find_map(|name| self.get_direct(name, key))
+	}
+
+	fn get_direct(&self, name: &str, key: &str) -> Option<String> {
+		let env_name = format!(
+			"CARGO_PROFILE_{}_{}",
+			name.to_ascii_uppercase().replace('-', "_"),
+			key.to_ascii_uppercase().replace('-', "_")
+		);
+		(self.env)(&env_name).or_else(|| {
+			let value = self.manifest?.get("profile")?.get(name)?.get(key)?;
+			match value {
+				toml::Value::String(value) => Some(value.clone()),
+				toml::Value::Integer(value) => Some(value.to_string()),
+				toml::Value::Boolean(value) => Some(value.to_string()),
+				_ => None,
+			}
+		})
+	}
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+	match value {
+		"true" | "on" | "yes" | "y" => Some(true),
+		"false" | "off" | "no" | "n" => Some(false),
+		_ => None,
+	}
+}
+
+fn normalize_debuginfo(debug: &str) -> String {
+	match debug {
+		"0" | "false" | "none" => "none",
+		"1" | "limited" => "limited",
+		"2" | "true" | "full" => "full",
+		debug => debug,
+	}
+	.to_string()
+}
+
+#[cfg(test)]
+mod test {
+	use std::collections::HashMap;
+
+	use pretty_assertions::assert_eq;
+
+	use super::*;
+
+	fn resolve_with(profile_dir: &str, manifest: &str, env: &[(&str, &str)]) -> ProfileSettings {
+		let manifest: toml::Value = manifest.parse().unwrap();
+		let env: HashMap<String, String> = env
+			.iter()
+			.map(|(key, value)| (key.to_string(), value.to_string()))
+			.collect();
+		resolve(profile_dir, Some(&manifest), &|name| env.get(name).cloned())
+	}
+
+	#[test]
+	fn builtin_defaults() {
+		assert_eq!(
+			resolve_with("debug", "", &[("CARGO_CFG_DEBUG_ASSERTIONS", "")]),
+			ProfileSettings {
+				debug_assertions: true,
+				overflow_checks: true,
+				panic: PanicStrategy::Unwind,
+				lto: "false".to_string(),
+				codegen_units: 256,
+				debuginfo: "full".to_string(),
+			}
+		);
+		assert_eq!(
+			resolve_with("release", "", &[("DEBUG", "false")]),
+			ProfileSettings {
+				debug_assertions: false,
+				overflow_checks: false,
+				panic: PanicStrategy::Unwind,
+				lto: "false".to_string(),
+				codegen_units: 16,
+				debuginfo: "none".to_string(),
+			}
+		);
+	}
+
+	#[test]
+	fn custom_profile_inherits() {
+		let manifest = r#"
+			[profile.release]
+			panic = "abort"
+			overflow-checks = true
+
+			[profile.release-lto]
+			inherits = "release"
+			lto = true
+			codegen-units = 1
+			debug = 1
+		"#;
+		assert_eq!(
+			resolve_with("release-lto", manifest, &[]),
+			ProfileSettings {
+				debug_assertions: false,
+				overflow_checks: true,
+				panic: PanicStrategy::Abort,
+				lto: "fat".to_string(),
+				codegen_units: 1,
+				debuginfo: "limited".to_string(),
+			}
+		);
+	}
+
+	#[test]
+	fn cfg_overrides_profile() {
+		let manifest = r#"
+			[profile.dev]
+			panic = "abort"
+		"#;
+		// Debug assertions were disabled via `RUSTFLAGS`, and the panic strategy via `.cargo/config.toml`
+		let settings = resolve_with(
+			"debug",
+			manifest,
+			&[("CARGO_CFG_TARGET_ARCH", "x86_64"), ("CARGO_CFG_PANIC", "unwind")],
+		);
+		assert!(!settings.debug_assertions);
+		assert_eq!(settings.panic, PanicStrategy::Unwind);
+
+		let settings = resolve_with(
+			"release",
+			"",
+			&[("CARGO_CFG_TARGET_ARCH", "x86_64"), ("CARGO_CFG_DEBUG_ASSERTIONS", "")],
+		);
+		assert!(settings.debug_assertions);
+	}
+
+	#[test]
+	fn environment_overrides_manifest() {
+		let manifest = r#"
+			[profile.release]
+			lto = "thin"
+		"#;
+		let settings = resolve_with(
+			"release",
+			manifest,
+			&[
+				("CARGO_PROFILE_RELEASE_LTO", "off"),
+				("CARGO_PROFILE_RELEASE_PANIC", "abort"),
+			],
+		);
+		assert_eq!(settings.lto, "off");
+		assert_eq!(settings.panic, PanicStrategy::Abort);
+	}
+}
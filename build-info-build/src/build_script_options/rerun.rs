@@ -0,0 +1,187 @@
+use std::path::{Path, PathBuf};
+
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use ignore::WalkBuilder;
+
+use crate::BuildInfoError;
+
+/// Determines which files in the crate directory cause the build script to be rerun
+pub(crate) struct RerunOptions {
+	include: Vec<String>,
+	exclude: Vec<String>,
+	respect_gitignore: bool,
+}
+
+impl Default for RerunOptions {
+	fn default() -> Self {
+		Self {
+			include: vec!["**/*.rs".to_string()],
+			exclude: Vec::new(),
+			respect_gitignore: true,
+		}
+	}
+}
+
+impl crate::BuildScriptOptions {
+	/// Reruns the build script when a file matching `pattern` changes, in addition to the Rust sources (`**/*.rs`).
+	///
+	/// Patterns are relative to the crate directory, and `*` does not match `/`, so use `**/` to match in any
+	/// subdirectory, e.g., `"templates/**/*.html"` or `"**/*.proto"`.
+	pub fn rerun_include(mut self, pattern: impl Into<String>) -> Self {
+		self.rerun.include.push(pattern.into());
+		self
+	}
+
+	/// Does not rerun the build script for files and directories matching `pattern`, even if they are included.
+	///
+	/// Patterns are relative to the crate directory, e.g., `"vendor"` or `"src/generated/**"`.
+	pub fn rerun_exclude(mut self, pattern: impl Into<String>) -> Self {
+		self.rerun.exclude.push(pattern.into());
+		self
+	}
+
+	/// Skips files that are ignored via `.gitignore`, `.ignore` or `.git/info/exclude` (enabled by default).
+	///
+	/// Independently of this setting, cargo's target directories, `.git` and the directories of other packages are never
+	/// searched.
+	pub fn rerun_respect_gitignore(mut self, respect_gitignore: bool) -> Self {
+		self.rerun.respect_gitignore = respect_gitignore;
+		self
+	}
+}
+
+fn glob_set(patterns: &[String]) -> Result<GlobSet, BuildInfoError> {
+	let mut builder = GlobSetBuilder::new();
+	for pattern in patterns {
+		let glob = GlobBuilder::new(pattern.trim_end_matches('/'))
+			.literal_separator(true)
+			.build()
+			.map_err(|err| BuildInfoError::InvalidPattern {
+				pattern: pattern.clone(),
+				reason: err.kind().to_string(),
+			})?;
+		builder.add(glob);
+	}
+	builder.build().map_err(|err| BuildInfoError::InvalidPattern {
+		pattern: err.glob().map_or_else(String::new, |glob| glob.to_string()),
+		reason: err.kind().to_string(),
+	})
+}
+
+/// Lists the files below `root` that match the `RerunOptions`, in sorted order.
+pub(crate) fn project_files(root: &Path, options: &RerunOptions) -> Result<Vec<PathBuf>, BuildInfoError> {
+	let include = glob_set(&options.include)?;
+	let exclude = glob_set(&options.exclude)?;
+	// Excluding a directory also excludes its contents
+	let excluded = |relative: &Path| relative.ancestors().any(|path| exclude.is_match(path));
+
+	let filter_root = root.to_path_buf();
+	let filter_exclude = exclude.clone();
+	let walker = WalkBuilder::new(root)
+		.standard_filters(false)
+		.git_ignore(options.respect_gitignore)
+		.git_exclude(options.respect_gitignore)
+		.ignore(options.respect_gitignore)
+		.parents(options.respect_gitignore)
+		.require_git(false)
+		.filter_entry(move |entry| {
+			let path = entry.path();
+			if entry.depth() == 0 || !entry.file_type().is_some_and(|file_type| file_type.is_dir()) {
+				return true;
+			}
+			// Cargo marks its target directories with a `CACHEDIR.TAG` file
+			entry.file_name() != ".git"
+				&& !path.join("CACHEDIR.TAG").is_file()
+				&& !path.join("Cargo.toml").is_file()
+				&& path
+					.strip_prefix(&filter_root)
+					.map_or(true, |relative| !filter_exclude.is_match(relative))
+		})
+		.build();
+
+	let mut files = Vec::new();
+	// Files that cannot be read (e.g., due to missing permissions) cannot affect the build either
+	for entry in walker.filter_map(|entry| entry.ok()) {
+		if !entry.file_type().is_some_and(|file_type| file_type.is_file()) {
+			continue;
+		}
+		let relative = entry.path().strip_prefix(root).unwrap_or_else(|_| entry.path());
+		if include.is_match(relative) && !excluded(relative) {
+			files.push(entry.into_path());
+		}
+	}
+	files.sort();
+	Ok(files)
+}
+
+#[cfg(test)]
+mod test {
+	use pretty_assertions::assert_eq;
+
+	use super::*;
+
+	fn fixture() -> tempfile::TempDir {
+		let dir = tempfile::tempdir().unwrap();
+		for file in [
+			"Cargo.toml",
+			"build.rs",
+			"src/main.rs",
+			"src/generated/bindings.rs",
+			"proto/service.proto",
+			"target/CACHEDIR.TAG",
+			"target/debug/build/out/generated.rs",
+			"ignored/file.rs",
+			"member/Cargo.toml",
+			"member/src/lib.rs",
+		] {
+			let path = dir.path().join(file);
+			std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+			std::fs::write(path, "").unwrap();
+		}
+		std::fs::write(dir.path().join(".gitignore"), "/ignored\n").unwrap();
+		dir
+	}
+
+	fn relative_files(root: &Path, options: &RerunOptions) -> Vec<String> {
+		project_files(root, options)
+			.unwrap()
+			.iter()
+			.map(|path| path.strip_prefix(root).unwrap().to_str().unwrap().replace('\\', "/"))
+			.collect()
+	}
+
+	#[test]
+	fn default_options() {
+		let dir = fixture();
+		assert_eq!(
+			relative_files(dir.path(), &RerunOptions::default()),
+			vec!["build.rs", "src/generated/bindings.rs", "src/main.rs"]
+		);
+	}
+
+	#[test]
+	fn include_exclude_and_gitignore() {
+		let dir = fixture();
+		let options = RerunOptions {
+			include: vec!["**/*.rs".to_string(), "proto/*.proto".to_string()],
+			exclude: vec!["src/generated/".to_string()],
+			respect_gitignore: false,
+		};
+		assert_eq!(
+			relative_files(dir.path(), &options),
+			vec!["build.rs", "ignored/file.rs", "proto/service.proto", "src/main.rs"]
+		);
+	}
+
+	#[test]
+	fn invalid_pattern() {
+		let options = RerunOptions {
+			exclude: vec!["src/[".to_string()],
+			..Default::default()
+		};
+		assert!(matches!(
+			project_files(Path::new("."), &options),
+			Err(BuildInfoError::InvalidPattern { .. })
+		));
+	}
+}
@@ -0,0 +1,102 @@
+use build_info_common::{
+	semver::{BuildMetadata, Prerelease, Version},
+	GitInfo,
+};
+
+impl crate::BuildScriptOptions {
+	/// Derives a version number from the nearest git tag, in addition to the version from `Cargo.toml`.
+	///
+	/// If the repository contains tags of the current crate (see [`crate_tag_pattern`](Self::crate_tag_pattern)), the
+	/// nearest of them is used, otherwise the nearest tag that names a semantic version. Other tags, such as `nightly`,
+	/// are skipped.
+	///
+	/// A build of the tagged commit gets the version of the tag (a leading `v` is ignored). Builds of later commits or of
+	/// a dirty worktree get a development version of the next patch release, which records the number of commits since
+	/// the tag and the commit hash: with the tag `v1.4.2`, a build 17 commits later yields `1.4.3-dev.17+gabc1234`, and
+	/// `1.4.3-dev.17+gabc1234.dirty` if there are uncommitted changes. If the tag is a pre-release, such as `v1.5.0-rc.1`,
+	/// the development version builds on it instead (`1.5.0-rc.1.dev.17+gabc1234`).
+	///
+	/// The result is available as `CrateInfo::git_version`.
+	pub fn version_from_git_tags(mut self, version_from_git_tags: bool) -> Self {
+		self.version_from_git_tags = version_from_git_tags;
+		self
+	}
+}
+
+/// Parses a tag like `v1.4.2` or `1.4.2` as a version
+pub(crate) fn parse_tag(tag: &str) -> Option<Version> {
+	let version = tag.strip_prefix(['v', 'V']).unwrap_or(tag);
+	Version::parse(version).ok()
+}
+
+/// Prefers the tags of the current crate over other tags
+pub(crate) fn get_version(git: &GitInfo) -> Option<Version> {
+	let (version, distance) = match (&git.crate_tag_version, git.commits_since_crate_tag) {
+		(Some(version), Some(distance)) => (version.clone(), distance),
+		_ => (
+			parse_tag(git.nearest_version_tag.as_ref()?)?,
+			git.commits_since_version_tag?,
+		),
+	};
+	Some(version_from_tag(version, distance, &git.commit_short_id, git.dirty))
+}
+
+fn version_from_tag(mut version: Version, distance: u32, commit_short_id: &str, dirty: bool) -> Version {
+	if distance == 0 && !dirty {
+		return version;
+	}
+
+	let prerelease = if version.pre.is_empty() {
+		version.patch += 1;
+		format!("dev.{distance}")
+	} else {
+		format!("{}.dev.{distance}", version.pre)
+	};
+	version.pre = Prerelease::new(&prerelease).expect("A valid pre-release with an appended numeric identifier is valid");
+
+	let build = if dirty {
+		format!("g{commit_short_id}.dirty")
+	} else {
+		format!("g{commit_short_id}")
+	};
+	version.build = BuildMetadata::new(&build).unwrap_or(BuildMetadata::EMPTY);
+	version
+}
+
+#[cfg(test)]
+mod test {
+	use pretty_assertions::assert_eq;
+
+	use super::*;
+
+	fn version(tag: &str, distance: u32, dirty: bool) -> String {
+		version_from_tag(parse_tag(tag).unwrap(), distance, "abc1234", dirty).to_string()
+	}
+
+	#[test]
+	fn tagged_commit() {
+		assert_eq!(version("v1.4.2", 0, false), "1.4.2");
+		assert_eq!(version("1.5.0-rc.1", 0, false), "1.5.0-rc.1");
+	}
+
+	#[test]
+	fn development_versions() {
+		assert_eq!(version("v1.4.2", 17, false), "1.4.3-dev.17+gabc1234");
+		assert_eq!(version("v1.4.2", 17, true), "1.4.3-dev.17+gabc1234.dirty");
+		assert_eq!(version("v1.4.2", 0, true), "1.4.3-dev.0+gabc1234.dirty");
+		assert_eq!(version("v1.5.0-rc.1", 3, false), "1.5.0-rc.1.dev.3+gabc1234");
+	}
+
+	#[test]
+	fn development_versions_sort_between_releases() {
+		let dev = parse_tag(&version("v1.4.2", 17, false)).unwrap();
+		assert!(Version::new(1, 4, 2) < dev);
+		assert!(dev < Version::new(1, 4, 3));
+	}
+
+	#[test]
+	fn invalid_tags() {
+		assert_eq!(parse_tag("release-2020"), None);
+		assert_eq!(parse_tag("v1.4"), None);
+	}
+}
@@ -1,6 +1,8 @@
 // Note that we do not use `build_info_common::chrono` here.
 use chrono::{DateTime, TimeZone, Utc};
 
+use crate::BuildInfoError;
+
 impl crate::BuildScriptOptions {
 	/// Set the build timestamp by hand.
 	///
@@ -23,17 +25,23 @@ impl crate::BuildScriptOptions {
 	}
 }
 
-pub(crate) fn get_timestamp() -> DateTime<Utc> {
+pub(crate) fn get_timestamp() -> Result<DateTime<Utc>, BuildInfoError> {
 	get_timestamp_internal(std::env::var("SOURCE_DATE_EPOCH").ok())
 }
 
-fn get_timestamp_internal(epoch: Option<String>) -> DateTime<Utc> {
+fn get_timestamp_internal(epoch: Option<String>) -> Result<DateTime<Utc>, BuildInfoError> {
 	// https://reproducible-builds.org/specs/source-date-epoch/
 	if let Some(epoch) = epoch {
-		let epoch: i64 = epoch.parse().expect("Could not parse SOURCE_DATE_EPOCH");
-		Utc.timestamp(epoch, 0)
+		epoch
+			.parse()
+			.ok()
+			.and_then(|epoch| Utc.timestamp_opt(epoch, 0).single())
+			.ok_or(BuildInfoError::InvalidEnvVar {
+				name: "SOURCE_DATE_EPOCH".to_string(),
+				value: epoch,
+			})
 	} else {
-		Utc::now()
+		Ok(Utc::now())
 	}
 }
 
@@ -45,9 +53,9 @@ mod test {
 
 	#[test]
 	fn get_current_timestamp() {
-		let past = Utc.timestamp(1591113000, 0);
-		let now = get_timestamp_internal(None);
-		let future = Utc.timestamp(32503680000, 0);
+		let past = Utc.timestamp_opt(1591113000, 0).unwrap();
+		let now = get_timestamp_internal(None).unwrap();
+		let future = Utc.timestamp_opt(32503680000, 0).unwrap();
 		assert!(past < now);
 		assert!(now < future);
 	}
@@ -55,6 +63,17 @@ mod test {
 	#[test]
 	fn get_fixed_timestamp() {
 		let epoch = 1591113000;
-		assert_eq!(get_timestamp_internal(Some(epoch.to_string())), Utc.timestamp(epoch, 0));
+		assert_eq!(
+			get_timestamp_internal(Some(epoch.to_string())).unwrap(),
+			Utc.timestamp_opt(epoch, 0).unwrap()
+		);
+	}
+
+	#[test]
+	fn invalid_timestamp() {
+		assert!(matches!(
+			get_timestamp_internal(Some("yesterday".to_string())),
+			Err(BuildInfoError::InvalidEnvVar { .. })
+		));
 	}
 }
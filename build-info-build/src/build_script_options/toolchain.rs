@@ -0,0 +1,159 @@
+use std::path::{Path, PathBuf};
+
+use build_info_common::{
+	chrono::{Duration, NaiveDate},
+	semver::Version,
+	CompilerChannel,
+};
+
+/// Runs `$CARGO --version` and extracts the version number.
+pub(crate) fn cargo_version() -> Option<Version> {
+	let cargo = std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+	let output = std::process::Command::new(cargo).arg("--version").output().ok()?;
+	let output = String::from_utf8(output.stdout).ok()?;
+	parse_cargo_version(&output)
+}
+
+fn parse_cargo_version(output: &str) -> Option<Version> {
+	// The output looks like `cargo 1.62.0 (a748cf5a3 2022-06-08)`
+	output
+		.split_whitespace()
+		.nth(1)
+		.and_then(|version| Version::parse(version).ok())
+}
+
+/// The toolchain pinned via a `rust-toolchain.toml` or `rust-toolchain` file
+pub(crate) struct PinnedToolchain {
+	pub path: PathBuf,
+	pub channel: String,
+}
+
+/// Searches the crate directory and its ancestors for a toolchain file, the same way `rustup` does.
+pub(crate) fn pinned_toolchain() -> Option<PinnedToolchain> {
+	let manifest_dir = PathBuf::from(std::env::var_os("CARGO_MANIFEST_DIR")?);
+	for dir in manifest_dir.ancestors() {
+		for name in ["rust-toolchain", "rust-toolchain.toml"] {
+			let path = dir.join(name);
+			if let Ok(content) = std::fs::read_to_string(&path) {
+				if let Err(err) = super::rerun_if_changed(&path) {
+					println!("cargo:warning={}", err);
+				}
+				return parse_toolchain_file(&path, &content).map(|channel| PinnedToolchain { path, channel });
+			}
+		}
+	}
+	None
+}
+
+fn parse_toolchain_file(path: &Path, content: &str) -> Option<String> {
+	if let Ok(toml) = content.parse::<toml::Value>() {
+		return toml
+			.get("toolchain")
+			.and_then(|toolchain| toolchain.get("channel"))
+			.and_then(|channel| channel.as_str())
+			.map(|channel| channel.to_string());
+	}
+
+	// The legacy `rust-toolchain` file may also contain just the name of the toolchain
+	if path.extension().is_none() {
+		let channel = content.trim();
+		if !channel.is_empty() && !channel.contains(char::is_whitespace) {
+			return Some(channel.to_string());
+		}
+	}
+
+	None
+}
+
+/// Checks whether the compiler matches the pinned channel. Returns `None` if the pinned channel cannot be interpreted
+/// (e.g., for custom toolchains).
+pub(crate) fn matches_pin(
+	pin: &str,
+	version: &Version,
+	channel: CompilerChannel,
+	commit_date: Option<NaiveDate>,
+) -> Option<bool> {
+	let (pinned_channel, date) = match pin.split_once('-') {
+		Some((pinned_channel, date)) => (pinned_channel, Some(NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?)),
+		None => (pin, None),
+	};
+
+	let channel_matches = match pinned_channel {
+		"stable" => channel == CompilerChannel::Stable,
+		"beta" => channel == CompilerChannel::Beta,
+		"nightly" => channel == CompilerChannel::Nightly,
+		pinned_version => {
+			let parts = pinned_version
+				.split('.')
+				.map(|part| part.parse::<u64>().ok())
+				.collect::<Option<Vec<_>>>()?;
+			return match parts[..] {
+				[major, minor] => Some(version.major == major && version.minor == minor),
+				[major, minor, patch] => Some(version.major == major && version.minor == minor && version.patch == patch),
+				_ => None,
+			};
+		}
+	};
+
+	// Dated toolchains are usually built from a commit of the previous day
+	let date_matches = match (date, commit_date) {
+		(Some(date), Some(commit_date)) => commit_date == date || commit_date + Duration::days(1) == date,
+		(Some(_), None) => false,
+		(None, _) => true,
+	};
+
+	Some(channel_matches && date_matches)
+}
+
+#[cfg(test)]
+mod test {
+	use pretty_assertions::assert_eq;
+
+	use super::*;
+
+	#[test]
+	fn cargo_version_output() {
+		assert_eq!(
+			parse_cargo_version("cargo 1.62.0 (a748cf5a3 2022-06-08)\n"),
+			Some(Version::new(1, 62, 0))
+		);
+		assert_eq!(
+			parse_cargo_version("cargo 1.64.0-nightly (4d92f07f3 2022-07-21)"),
+			Some(Version::parse("1.64.0-nightly").unwrap())
+		);
+		assert_eq!(parse_cargo_version(""), None);
+	}
+
+	#[test]
+	fn toolchain_files() {
+		assert_eq!(
+			parse_toolchain_file(
+				Path::new("rust-toolchain.toml"),
+				"[toolchain]\nchannel = \"1.62.0\"\ncomponents = [\"clippy\"]\n"
+			),
+			Some("1.62.0".to_string())
+		);
+		assert_eq!(
+			parse_toolchain_file(Path::new("rust-toolchain"), "nightly-2022-07-01\n"),
+			Some("nightly-2022-07-01".to_string())
+		);
+	}
+
+	#[test]
+	fn pins() {
+		let version = Version::new(1, 62, 1);
+		let stable = CompilerChannel::Stable;
+		let date = NaiveDate::from_ymd_opt(2022, 7, 19);
+
+		assert_eq!(matches_pin("stable", &version, stable, date), Some(true));
+		assert_eq!(matches_pin("nightly", &version, stable, date), Some(false));
+		assert_eq!(matches_pin("1.62", &version, stable, date), Some(true));
+		assert_eq!(matches_pin("1.62.1", &version, stable, date), Some(true));
+		assert_eq!(matches_pin("1.61.0", &version, stable, date), Some(false));
+		assert_eq!(matches_pin("my-toolchain", &version, stable, date), None);
+
+		let nightly = CompilerChannel::Nightly;
+		assert_eq!(matches_pin("nightly-2022-07-20", &version, nightly, date), Some(true));
+		assert_eq!(matches_pin("nightly-2022-07-30", &version, nightly, date), Some(false));
+	}
+}
@@ -0,0 +1,397 @@
+use std::{
+	collections::BTreeMap,
+	ffi::OsString,
+	path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Result};
+use build_info_common::{
+	chrono::{DateTime, FixedOffset, TimeZone, Utc},
+	GitInfo, SubmoduleInfo,
+};
+
+use super::{
+	super::{rerun_if_changed, tag_version::parse_tag},
+	crate_tag::CrateTagPattern,
+	describe::{describe, NearestTag},
+	discovery::ref_path,
+	remote::sanitize_url,
+	CrateContext, VersionControlOptions,
+};
+use crate::BuildInfoError;
+
+/// The parts of the crate context that only the git backends need
+pub(crate) struct GitContext {
+	/// The name of the crate, which is used to find its tags
+	pub name: String,
+
+	/// The version of the crate, which is compared to the version of its tags
+	pub version: String,
+
+	/// The git directory given by `GIT_DIR`, which is used instead of discovering the repository
+	pub git_dir: Option<PathBuf>,
+
+	/// The worktree given by `GIT_WORK_TREE`, which overrides the worktree of the repository
+	pub git_work_tree: Option<PathBuf>,
+}
+
+impl GitContext {
+	pub(super) fn from_env() -> Result<Self, BuildInfoError> {
+		let path_var = |name: &str| {
+			println!("cargo:rerun-if-env-changed={}", name);
+			absolute_path(name, std::env::var_os(name))
+		};
+		Ok(Self {
+			name: BuildInfoError::env_var("CARGO_PKG_NAME")?,
+			version: BuildInfoError::env_var("CARGO_PKG_VERSION")?,
+			git_dir: path_var("GIT_DIR"),
+			git_work_tree: path_var("GIT_WORK_TREE"),
+		})
+	}
+}
+
+/// Interprets the value of the environment variable `name` as an absolute path.
+///
+/// Cargo runs build scripts in the directory of the manifest, not in the directory that cargo was started in, so relative
+/// paths cannot be resolved reliably; they are ignored with a warning.
+fn absolute_path(name: &str, value: Option<OsString>) -> Option<PathBuf> {
+	let path = PathBuf::from(value.filter(|value| !value.is_empty())?);
+	if path.is_relative() {
+		println!(
+			"cargo:warning=Ignoring {}={:?}, as relative paths are not supported; use an absolute path instead",
+			name, path
+		);
+		return None;
+	}
+	Some(path)
+}
+
+/// A commit, as far as it is part of `GitInfo`
+pub(super) struct Commit {
+	pub id: String,
+	pub short_id: String,
+	/// The commit time in seconds since the Unix epoch
+	pub time: i64,
+	pub author: Signature,
+	pub committer: Signature,
+	pub summary: Option<String>,
+	pub trailers: BTreeMap<String, Vec<String>>,
+}
+
+/// The author or committer of a commit
+pub(super) struct Signature {
+	/// `None` if the name is not valid UTF-8
+	pub name: Option<String>,
+	/// `None` if the email address is not valid UTF-8
+	pub email: Option<String>,
+	/// Seconds since the Unix epoch
+	pub time: i64,
+	/// The UTC offset in seconds
+	pub offset: i32,
+}
+
+/// The primitives of a git backend, from which `git_info` collects the information about a repository
+pub(super) trait GitBackend {
+	/// The git directory, which contains `HEAD` and the index
+	fn git_dir(&self) -> &Path;
+
+	/// The directory that contains the references and the configuration, which linked worktrees share with the main
+	/// worktree
+	fn common_dir(&self) -> &Path;
+
+	/// The root of the worktree; `None` if the repository is bare
+	fn workdir(&self) -> Option<&Path>;
+
+	/// The full name of the reference that `HEAD` points to; `None` if `HEAD` is detached
+	fn head_ref(&self) -> Result<Option<String>>;
+
+	fn head_commit(&self) -> Result<Commit>;
+
+	/// The names of all tags that point to `HEAD`
+	fn head_tags(&self) -> Result<Vec<String>>;
+
+	/// Finds the closest tag that is reachable from `HEAD` and is accepted by `accept`, the same way
+	/// `git describe --tags [--match $pattern]` does; the glob `pattern` only serves to narrow down the candidates early.
+	fn find_nearest_tag(&self, pattern: Option<&str>, accept: &dyn Fn(&str) -> bool) -> Result<Option<NearestTag>>;
+
+	/// Finds the last commit reachable from `HEAD` that changed `path` (relative to the root of the worktree), the same
+	/// way `git log -1 -- $path` does
+	fn last_commit_touching(&self, path: &Path) -> Result<Option<Commit>>;
+
+	/// The full name of the upstream of the local branch `branch`, if it has one and the upstream exists
+	fn upstream(&self, branch: &str) -> Result<Option<String>>;
+
+	/// The number of commits that are only reachable from `HEAD`, and the number of commits that are only reachable from
+	/// `reference`
+	fn ahead_behind(&self, reference: &str) -> Result<(u32, u32)>;
+
+	/// Checks for changes to the index or the worktree, optionally restricted to `pathspec`; like
+	/// `git status --untracked-files=no`, untracked files do not count.
+	fn is_dirty(&self, pathspec: Option<&str>) -> Result<bool>;
+
+	/// The files that can change the result of `git status`: the index, the files it tracks and the untracked files.
+	fn dirty_tracking_paths(&self) -> Result<Vec<PathBuf>>;
+
+	/// The value of the configuration variable `key`, if it is set
+	fn config(&self, key: &str) -> Result<Option<String>>;
+
+	/// The URLs of all remotes by their names, as they are configured; remotes without a valid UTF-8 URL are skipped
+	fn remotes(&self) -> Result<BTreeMap<String, String>>;
+
+	fn submodules(&self) -> Result<Vec<SubmoduleInfo>>;
+}
+
+/// Collects the information about the repository that contains the crate
+pub(super) fn git_info(
+	repository: &impl GitBackend,
+	krate: &CrateContext,
+	options: &VersionControlOptions,
+) -> Result<GitInfo> {
+	rerun_if_changed(&repository.git_dir().join("HEAD"))?;
+
+	let head = repository.head_ref()?;
+	if let Some(name) = &head {
+		rerun_if_ref_changed(repository, name)?;
+	}
+	let branch = head
+		.as_deref()
+		.and_then(|name| name.strip_prefix("refs/heads/"))
+		.map(|branch| branch.to_string());
+	let commit = repository.head_commit()?;
+	let commit_timestamp = utc_timestamp(commit.time)?;
+
+	// A bare repository does not have a worktree that could contain changes
+	let dirty = repository.workdir().is_some() && repository.is_dirty(None)?;
+	if options.track_dirty && repository.workdir().is_some() {
+		for path in repository.dirty_tracking_paths()? {
+			rerun_if_changed(&path)?;
+		}
+	}
+
+	let mut tags = repository.head_tags()?;
+	tags.sort();
+
+	// Remotes are configured in the repository configuration
+	rerun_if_changed(&repository.common_dir().join("config"))?;
+	let remotes = repository
+		.remotes()?
+		.into_iter()
+		.map(|(name, url)| (name, sanitize_url(&url)))
+		.collect();
+	let (upstream_remote, upstream) = match (&head, &branch) {
+		(Some(head), Some(branch)) => (
+			repository.config(&format!("branch.{}.remote", branch))?,
+			upstream(repository, head)?,
+		),
+		_ => (None, None),
+	};
+
+	let identity = |value: &Option<String>| if options.commit_identities { value.clone() } else { None };
+	let timestamp = |signature: &Signature| {
+		if options.commit_timestamps {
+			to_timestamp(signature.time, signature.offset).map(Some)
+		} else {
+			Ok(None)
+		}
+	};
+
+	let crate_path = repository
+		.workdir()
+		.and_then(|workdir| crate_path(workdir, &krate.manifest_dir));
+	let crate_commit = match &crate_path {
+		Some(crate_path) => repository.last_commit_touching(crate_path)?,
+		None => None,
+	};
+	let crate_commit_timestamp = crate_commit
+		.as_ref()
+		.map(|commit| utc_timestamp(commit.time))
+		.transpose()?;
+	let crate_dirty = match &crate_path {
+		Some(crate_path) if !crate_path.as_os_str().is_empty() => {
+			let pathspec = crate_path
+				.to_str()
+				.ok_or_else(|| anyhow!("The path of the crate is not valid UTF-8"))?;
+			repository.is_dirty(Some(pathspec))?
+		}
+		_ => dirty,
+	};
+
+	// Creating or deleting a tag may change the description
+	for path in [
+		repository.common_dir().join("refs/tags"),
+		repository.common_dir().join("packed-refs"),
+	] {
+		if path.exists() {
+			rerun_if_changed(&path)?;
+		}
+	}
+	let nearest_tag = repository.find_nearest_tag(None, &|_| true)?;
+	let describe = describe(nearest_tag.as_ref(), &commit.short_id, dirty);
+	let nearest_version_tag = match &nearest_tag {
+		Some(tag) if parse_tag(&tag.name).is_some() => Some(tag.clone()),
+		_ => repository.find_nearest_tag(None, &|name| parse_tag(name).is_some())?,
+	};
+
+	let crate_tag_pattern = CrateTagPattern::new(&options.crate_tag_pattern, &krate.git.name)?;
+	let crate_tags = tags
+		.iter()
+		.filter(|tag| crate_tag_pattern.version(tag).is_some())
+		.cloned()
+		.collect();
+	let nearest_crate_tag = repository.find_nearest_tag(Some(&crate_tag_pattern.glob()), &|name| {
+		crate_tag_pattern.version(name).is_some()
+	})?;
+	let (crate_tag_version, crate_tag_version_matches) =
+		crate_tag_pattern.check_version(nearest_crate_tag.as_ref(), &krate.git.version);
+
+	Ok(GitInfo {
+		commit_id: commit.id,
+		commit_short_id: commit.short_id,
+		commit_timestamp,
+		dirty,
+		branch,
+		tags,
+		commits_since_tag: nearest_tag.as_ref().map(|tag| tag.distance),
+		nearest_tag: nearest_tag.map(|tag| tag.name),
+		commits_since_version_tag: nearest_version_tag.as_ref().map(|tag| tag.distance),
+		nearest_version_tag: nearest_version_tag.map(|tag| tag.name),
+		describe,
+		crate_tags,
+		commits_since_crate_tag: nearest_crate_tag.as_ref().map(|tag| tag.distance),
+		nearest_crate_tag: nearest_crate_tag.map(|tag| tag.name),
+		crate_tag_version,
+		crate_tag_version_matches,
+		crate_commit_id: crate_commit.map(|commit| commit.id),
+		crate_commit_timestamp,
+		crate_dirty,
+		remotes,
+		upstream_remote,
+		upstream_branch: upstream.as_ref().map(|upstream| upstream.name.clone()),
+		commits_ahead: upstream.as_ref().map(|upstream| upstream.ahead),
+		commits_behind: upstream.as_ref().map(|upstream| upstream.behind),
+		author_name: identity(&commit.author.name),
+		author_email: identity(&commit.author.email),
+		committer_name: identity(&commit.committer.name),
+		committer_email: identity(&commit.committer.email),
+		author_timestamp: timestamp(&commit.author)?,
+		committer_timestamp: timestamp(&commit.committer)?,
+		summary: if options.commit_summary { commit.summary } else { None },
+		trailers: if options.commit_trailers {
+			commit.trailers
+		} else {
+			BTreeMap::new()
+		},
+		submodules: repository.submodules()?,
+	})
+}
+
+/// Watches the file of a loose reference, or the packed references if `name` is not stored as a loose reference
+pub(super) fn rerun_if_ref_changed(repository: &impl GitBackend, name: &str) -> Result<()> {
+	let path = ref_path(repository.git_dir(), repository.common_dir(), name);
+	if path.is_file() {
+		rerun_if_changed(&path)?;
+	} else {
+		rerun_if_changed(&repository.common_dir().join("packed-refs"))?;
+	}
+	Ok(())
+}
+
+struct Upstream {
+	name: String,
+	ahead: u32,
+	behind: u32,
+}
+
+/// Compares the current branch with its upstream, if it has one
+fn upstream(repository: &impl GitBackend, branch: &str) -> Result<Option<Upstream>> {
+	let upstream = match repository.upstream(branch)? {
+		Some(upstream) => upstream,
+		None => return Ok(None),
+	};
+
+	// The upstream changes whenever it is fetched or pushed to
+	rerun_if_ref_changed(repository, &upstream)?;
+
+	let (ahead, behind) = repository.ahead_behind(&upstream)?;
+	Ok(Some(Upstream {
+		name: short_name(&upstream).to_string(),
+		ahead,
+		behind,
+	}))
+}
+
+/// The shortest unambiguous name of a branch, e.g., `origin/main` for `refs/remotes/origin/main`
+fn short_name(reference: &str) -> &str {
+	["refs/heads/", "refs/remotes/", "refs/"]
+		.iter()
+		.find_map(|prefix| reference.strip_prefix(prefix))
+		.unwrap_or(reference)
+}
+
+fn utc_timestamp(seconds: i64) -> Result<DateTime<Utc>> {
+	Utc
+		.timestamp_opt(seconds, 0)
+		.single()
+		.ok_or_else(|| anyhow!("Encountered a commit with an invalid timestamp"))
+}
+
+fn to_timestamp(seconds: i64, offset: i32) -> Result<DateTime<FixedOffset>> {
+	FixedOffset::east_opt(offset)
+		.and_then(|offset| offset.timestamp_opt(seconds, 0).single())
+		.ok_or_else(|| anyhow!("Encountered a commit with an invalid timestamp"))
+}
+
+/// The directory of the current crate, relative to the root of the worktree; `None` if the crate is not part of the
+/// worktree (e.g., because `GIT_WORK_TREE` points elsewhere)
+fn crate_path(workdir: &Path, manifest_dir: &Path) -> Option<PathBuf> {
+	let canonicalize = |path: &Path| std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+	let path = canonicalize(manifest_dir)
+		.strip_prefix(canonicalize(workdir))
+		.ok()?
+		.to_path_buf();
+	Some(path)
+}
+
+/// The path that is watched for the tracked file `path`, if any.
+///
+/// Cargo considers a missing path to be changed on every build, so a tracked file that was deleted from the worktree is
+/// replaced by its closest existing parent directory, which changes when the file is restored. As cargo scans directories
+/// recursively, the root of the worktree (which usually contains the target directory) is not watched; files deleted
+/// from it are only noticed once the deletion is staged.
+pub(super) fn tracked_path(workdir: &Path, path: &str) -> Option<PathBuf> {
+	workdir
+		.join(path)
+		.ancestors()
+		.take_while(|ancestor| *ancestor != workdir)
+		.find(|ancestor| ancestor.exists())
+		.map(Path::to_path_buf)
+}
+
+#[cfg(test)]
+mod test {
+	use pretty_assertions::assert_eq;
+
+	use super::*;
+
+	#[test]
+	fn only_absolute_paths() {
+		let absolute = std::env::temp_dir().join("repo.git");
+		assert_eq!(absolute_path("GIT_DIR", Some(absolute.clone().into())), Some(absolute));
+		assert_eq!(absolute_path("GIT_DIR", Some("repo.git".into())), None);
+		assert_eq!(absolute_path("GIT_DIR", Some("".into())), None);
+		assert_eq!(absolute_path("GIT_DIR", None), None);
+	}
+
+	#[test]
+	fn timestamp_keeps_offset() {
+		let timestamp = to_timestamp(1591113000, 7200).unwrap();
+		assert_eq!(timestamp.to_rfc3339(), "2020-06-02T17:50:00+02:00");
+	}
+
+	#[test]
+	fn short_names() {
+		assert_eq!(short_name("refs/remotes/origin/main"), "origin/main");
+		assert_eq!(short_name("refs/heads/feature/x"), "feature/x");
+		assert_eq!(short_name("HEAD"), "HEAD");
+	}
+}
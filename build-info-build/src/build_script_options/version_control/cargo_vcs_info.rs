@@ -0,0 +1,93 @@
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use build_info_common::CargoVcsInfo;
+
+use super::super::rerun_if_changed;
+
+/// Returns `None` if the crate was not packaged from a git repository.
+///
+/// `cargo package` writes `.cargo_vcs_info.json` next to the manifest of the packaged crate.
+pub(crate) fn get_info(manifest_dir: &Path) -> Result<Option<CargoVcsInfo>> {
+	let path = manifest_dir.join(".cargo_vcs_info.json");
+	if !path.is_file() {
+		return Ok(None);
+	}
+	rerun_if_changed(&path)?;
+
+	let contents = std::fs::read_to_string(&path).with_context(|| format!("Could not read {}", path.display()))?;
+	parse(&contents).with_context(|| format!("Could not parse {}", path.display()))
+}
+
+/// Parses the contents of `.cargo_vcs_info.json`, e.g.,
+/// `{"git": {"sha1": "...", "dirty": true}, "path_in_vcs": "build-info-build"}`
+fn parse(contents: &str) -> Result<Option<CargoVcsInfo>> {
+	let info: serde_json::Value = serde_json::from_str(contents)?;
+
+	// Cargo only records git repositories
+	let git = match info.get("git") {
+		Some(git) => git,
+		None => return Ok(None),
+	};
+	let commit_id = git["sha1"]
+		.as_str()
+		.filter(|sha1| sha1.len() >= 7 && sha1.bytes().all(|byte| byte.is_ascii_hexdigit()))
+		.ok_or_else(|| anyhow!("The commit is not a valid git commit id"))?;
+
+	Ok(Some(CargoVcsInfo {
+		commit_id: commit_id.to_string(),
+		commit_short_id: commit_id[..7].to_string(),
+		// Cargo only writes the `dirty` flag if it is set
+		dirty: git["dirty"].as_bool().unwrap_or(false),
+		// Cargo versions before 1.58 did not record the path
+		path_in_vcs: info["path_in_vcs"].as_str().unwrap_or_default().to_string(),
+	}))
+}
+
+#[cfg(test)]
+mod test {
+	use pretty_assertions::assert_eq;
+
+	use super::*;
+
+	#[test]
+	fn packaged_crate() {
+		let info = parse(
+			r#"{
+  "git": {
+    "sha1": "2b4a5dc0e2a1f9e3c1d7b1e33bd0e7d36a4c2b8f",
+    "dirty": true
+  },
+  "path_in_vcs": "build-info-build"
+}"#,
+		)
+		.unwrap();
+		assert_eq!(
+			info,
+			Some(CargoVcsInfo {
+				commit_id: "2b4a5dc0e2a1f9e3c1d7b1e33bd0e7d36a4c2b8f".to_string(),
+				commit_short_id: "2b4a5dc".to_string(),
+				dirty: true,
+				path_in_vcs: "build-info-build".to_string(),
+			})
+		);
+	}
+
+	#[test]
+	fn optional_fields() {
+		let info = parse(r#"{"git": {"sha1": "2b4a5dc0e2a1f9e3c1d7b1e33bd0e7d36a4c2b8f"}}"#)
+			.unwrap()
+			.unwrap();
+		assert!(!info.dirty);
+		assert_eq!(info.path_in_vcs, "");
+
+		assert_eq!(parse(r#"{"path_in_vcs": ""}"#).unwrap(), None);
+		assert!(parse(r#"{"git": {"sha1": "HEAD"}}"#).is_err());
+	}
+
+	#[test]
+	fn missing_file() {
+		let dir = tempfile::tempdir().unwrap();
+		assert_eq!(get_info(dir.path()).unwrap(), None);
+	}
+}
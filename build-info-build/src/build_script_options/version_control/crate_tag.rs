@@ -0,0 +1,87 @@
+use anyhow::{anyhow, Result};
+use build_info_common::semver::Version;
+
+use super::describe::NearestTag;
+
+/// A crate tag pattern with the crate name already filled in
+#[derive(Eq, PartialEq, Debug)]
+pub(crate) struct CrateTagPattern {
+	prefix: String,
+	suffix: String,
+}
+
+impl CrateTagPattern {
+	pub fn new(pattern: &str, crate_name: &str) -> Result<Self> {
+		let pattern = pattern.replace("{crate}", crate_name);
+		let (prefix, suffix) = pattern
+			.split_once("{version}")
+			.ok_or_else(|| anyhow!("The crate tag pattern {:?} does not contain `{{version}}`", pattern))?;
+		Ok(Self {
+			prefix: prefix.to_string(),
+			suffix: suffix.to_string(),
+		})
+	}
+
+	/// A glob that matches all tags that may match this pattern, as understood by `git describe --match`
+	pub fn glob(&self) -> String {
+		format!("{}*{}", self.prefix, self.suffix)
+	}
+
+	/// Parses the version from `tag`, if the tag matches this pattern
+	pub fn version(&self, tag: &str) -> Option<Version> {
+		let version = tag.strip_prefix(&self.prefix)?.strip_suffix(&self.suffix)?;
+		Version::parse(version).ok()
+	}
+
+	/// The version of the nearest crate tag and whether it agrees with `crate_version`.
+	///
+	/// Emits a warning if the current commit carries a crate tag that disagrees with `crate_version`.
+	pub fn check_version(
+		&self,
+		nearest_tag: Option<&NearestTag>,
+		crate_version: &str,
+	) -> (Option<Version>, Option<bool>) {
+		let version = nearest_tag.and_then(|tag| self.version(&tag.name));
+		let matches = version.as_ref().map(|version| version.to_string() == crate_version);
+		if let (Some(tag), Some(false)) = (nearest_tag, matches) {
+			if tag.distance == 0 {
+				println!(
+					"cargo:warning=The tag {:?} does not agree with the crate version {}",
+					tag.name, crate_version,
+				);
+			}
+		}
+		(version, matches)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use pretty_assertions::assert_eq;
+
+	use super::*;
+
+	#[test]
+	fn default_pattern() {
+		let pattern = CrateTagPattern::new(super::super::DEFAULT_CRATE_TAG_PATTERN, "sample").unwrap();
+		assert_eq!(pattern.glob(), "sample@v*");
+		assert_eq!(pattern.version("sample@v0.0.10"), Some(Version::new(0, 0, 10)));
+		assert_eq!(pattern.version("other@v0.0.10"), None);
+		assert_eq!(pattern.version("sample@vnext"), None);
+	}
+
+	#[test]
+	fn custom_pattern() {
+		let pattern = CrateTagPattern::new("release/{crate}-{version}-final", "build-info").unwrap();
+		assert_eq!(pattern.glob(), "release/build-info-*-final");
+		assert_eq!(
+			pattern.version("release/build-info-1.0.0-rc.1-final"),
+			Some(Version::parse("1.0.0-rc.1").unwrap())
+		);
+	}
+
+	#[test]
+	fn pattern_without_version() {
+		assert!(CrateTagPattern::new("{crate}", "sample").is_err());
+	}
+}
@@ -0,0 +1,40 @@
+/// The closest tag that is reachable from `HEAD`, as found by `git describe --tags`
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub(crate) struct NearestTag {
+	pub name: String,
+	pub distance: u32,
+	/// The abbreviated commit hash, prefixed with `g`
+	pub suffix: String,
+}
+
+/// Formats the description of `HEAD` like `git describe --tags --always --dirty`
+pub(crate) fn describe(nearest_tag: Option<&NearestTag>, commit_short_id: &str, dirty: bool) -> String {
+	let mut describe = match nearest_tag {
+		Some(tag) if tag.distance == 0 => tag.name.clone(),
+		Some(tag) => format!("{}-{}-{}", tag.name, tag.distance, tag.suffix),
+		None => commit_short_id.to_string(),
+	};
+	if dirty {
+		describe.push_str("-dirty");
+	}
+	describe
+}
+
+#[cfg(test)]
+mod test {
+	use pretty_assertions::assert_eq;
+
+	use super::*;
+
+	#[test]
+	fn describe_format() {
+		let tag = |distance| NearestTag {
+			name: "v1.4.2".to_string(),
+			distance,
+			suffix: "gabc1234".to_string(),
+		};
+		assert_eq!(describe(Some(&tag(17)), "abc1234", true), "v1.4.2-17-gabc1234-dirty");
+		assert_eq!(describe(Some(&tag(0)), "abc1234", false), "v1.4.2");
+		assert_eq!(describe(None, "abc1234", true), "abc1234-dirty");
+	}
+}
@@ -0,0 +1,50 @@
+use std::path::{Path, PathBuf};
+
+/// The file of the loose reference `name`: references that belong to a single worktree, such as `HEAD`, are stored in
+/// its git directory, all other references in the common directory.
+pub(crate) fn ref_path(git_dir: &Path, common_dir: &Path, name: &str) -> PathBuf {
+	let per_worktree = !name.starts_with("refs/")
+		|| ["refs/worktree/", "refs/bisect/", "refs/rewritten/"]
+			.iter()
+			.any(|prefix| name.starts_with(prefix));
+	if per_worktree {
+		git_dir.join(name)
+	} else {
+		common_dir.join(name)
+	}
+}
+
+/// Git refuses to open repositories that belong to a different user, which commonly happens when a checkout is mounted
+/// into a container. As the crate still builds, the missing information would go unnoticed without a warning.
+pub(crate) fn warn_dubious_ownership(path: &Path) {
+	println!(
+		"cargo:warning=The git repository at {} is owned by a different user, so no version control information is \
+		 collected. To trust it, run `git config --global --add safe.directory {}`",
+		path.display(),
+		path.display(),
+	);
+}
+
+#[cfg(test)]
+mod test {
+	use pretty_assertions::assert_eq;
+
+	use super::*;
+
+	#[test]
+	fn linked_worktree() {
+		let dir = tempfile::tempdir().unwrap();
+		let git_dir = dir.path().join(".git/worktrees/feature");
+		let common_dir = dir.path().join(".git");
+
+		assert_eq!(ref_path(&git_dir, &common_dir, "HEAD"), git_dir.join("HEAD"));
+		assert_eq!(
+			ref_path(&git_dir, &common_dir, "refs/heads/main"),
+			common_dir.join("refs/heads/main")
+		);
+		assert_eq!(
+			ref_path(&git_dir, &common_dir, "refs/bisect/bad"),
+			git_dir.join("refs/bisect/bad")
+		);
+	}
+}
@@ -0,0 +1,557 @@
+use std::{
+	collections::BTreeMap,
+	io::ErrorKind,
+	path::{Path, PathBuf},
+	process::{Command, Output},
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use build_info_common::{chrono::DateTime, GitInfo, SubmoduleInfo};
+
+use super::{
+	super::rerun_if_changed,
+	backend::{self, git_info, tracked_path, GitBackend, Signature},
+	describe::NearestTag,
+	discovery::warn_dubious_ownership,
+	git_output::{parse_long_describe, quoted_path},
+	CrateContext, VersionControlOptions,
+};
+
+/// The format of `git log` for a `Commit`, with fields separated by NUL bytes; the trailers come last, as they span
+/// multiple lines
+const COMMIT_FORMAT: &str =
+	"--format=%H%x00%h%x00%ct%x00%an%x00%ae%x00%aI%x00%cn%x00%ce%x00%cI%x00%s%x00%(trailers:only,unfold)";
+
+/// Returns `None` if the crate is not part of a git repository, if the repository belongs to a different user, or if
+/// `git` is not installed.
+pub(crate) fn get_info(krate: &CrateContext, options: &VersionControlOptions) -> Result<Option<GitInfo>> {
+	let repository = Repository::discover(
+		&krate.manifest_dir,
+		krate.git.git_dir.as_deref(),
+		krate.git.git_work_tree.as_deref(),
+	)?;
+	match repository {
+		Some(repository) => Ok(Some(git_info(&repository, krate, options)?)),
+		None => Ok(None),
+	}
+}
+
+/// A repository, which is passed to every command explicitly, so that `GIT_DIR` and `GIT_WORK_TREE` in the environment
+/// of the build script do not apply to other repositories, such as submodules
+struct Repository {
+	git_dir: PathBuf,
+	/// Contains the references and the configuration, which linked worktrees share with the main worktree
+	common_dir: PathBuf,
+	/// `None` if the repository is bare
+	workdir: Option<PathBuf>,
+}
+
+impl Repository {
+	/// Opens `git_dir` (with the worktree `work_tree`), or discovers the repository that contains `dir`.
+	///
+	/// Returns `None` if there is no repository, if the repository belongs to a different user, or if `git` is not
+	/// installed.
+	fn discover(dir: &Path, git_dir: Option<&Path>, work_tree: Option<&Path>) -> Result<Option<Self>> {
+		let command = |dir: &Path, args: &[&str]| {
+			let mut command = git_command(dir);
+			command.args(args);
+			if let Some(git_dir) = git_dir {
+				command.env("GIT_DIR", git_dir);
+			}
+			if let Some(work_tree) = work_tree {
+				command.env("GIT_WORK_TREE", work_tree);
+			}
+			command
+		};
+
+		let args = [
+			"rev-parse",
+			"--absolute-git-dir",
+			"--git-common-dir",
+			"--is-bare-repository",
+		];
+		let output = match command(dir, &args).output() {
+			Ok(output) => output,
+			Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+			Err(err) => return Err(err).context("Could not run `git`"),
+		};
+		if !output.status.success() {
+			let stderr = String::from_utf8_lossy(&output.stderr);
+			if stderr.contains("dubious ownership") || stderr.contains("unsafe repository") {
+				warn_dubious_ownership(&quoted_path(&stderr).unwrap_or_else(|| dir.to_path_buf()));
+				return Ok(None);
+			}
+			if stderr.contains("not a git repository") && git_dir.is_none() {
+				return Ok(None);
+			}
+		}
+		let output = stdout(&args, output)?;
+		let (resolved_git_dir, common_dir, bare) = match output.lines().collect::<Vec<_>>()[..] {
+			[git_dir, common_dir, bare] => (PathBuf::from(git_dir), dir.join(common_dir), bare == "true"),
+			_ => bail!("`git rev-parse` returned an unexpected output: {:?}", output),
+		};
+
+		let workdir = if bare {
+			None
+		} else {
+			// Without `GIT_WORK_TREE` or `core.worktree`, git uses the current directory as the worktree of `GIT_DIR`;
+			// the parent directory is used instead, like for a discovered repository.
+			let toplevel_dir = match git_dir {
+				Some(_) => resolved_git_dir.parent().unwrap_or(&resolved_git_dir),
+				None => dir,
+			};
+			let args = ["rev-parse", "--show-toplevel"];
+			let toplevel = stdout(
+				&args,
+				command(toplevel_dir, &args).output().context("Could not run `git`")?,
+			)?;
+			Some(PathBuf::from(toplevel.trim_end()))
+		};
+
+		Ok(Some(Self {
+			git_dir: resolved_git_dir,
+			common_dir,
+			workdir,
+		}))
+	}
+
+	fn command(&self, args: &[&str]) -> Command {
+		let mut command = git_command(self.workdir.as_deref().unwrap_or(&self.git_dir));
+		command.arg("--git-dir").arg(&self.git_dir);
+		if let Some(workdir) = &self.workdir {
+			command.arg("--work-tree").arg(workdir);
+		}
+		command.args(args);
+		command
+	}
+
+	fn git(&self, args: &[&str]) -> Result<String> {
+		let output = self.command(args).output().context("Could not run `git`")?;
+		stdout(args, output)
+	}
+
+	/// Runs a command that exits with status 1 if the requested object does not exist, e.g., `git config $key`
+	fn git_optional(&self, args: &[&str]) -> Result<Option<String>> {
+		let output = self.command(args).output().context("Could not run `git`")?;
+		if output.status.code() == Some(1) {
+			return Ok(None);
+		}
+		stdout(args, output).map(Some)
+	}
+}
+
+fn git_command(dir: &Path) -> Command {
+	let mut command = Command::new("git");
+	command
+		.current_dir(dir)
+		.env_remove("GIT_DIR")
+		.env_remove("GIT_WORK_TREE")
+		// The error messages are matched, so they must not be localized
+		.env("LC_ALL", "C")
+		// Do not refresh the index as a side effect of `git status`, which would rerun the build script
+		.env("GIT_OPTIONAL_LOCKS", "0");
+	command
+}
+
+fn stdout(args: &[&str], output: Output) -> Result<String> {
+	if !output.status.success() {
+		bail!(
+			"`git {}` failed: {}",
+			args.join(" "),
+			String::from_utf8_lossy(&output.stderr).trim()
+		);
+	}
+	Ok(String::from_utf8(output.stdout)?)
+}
+
+impl GitBackend for Repository {
+	fn git_dir(&self) -> &Path {
+		&self.git_dir
+	}
+
+	fn common_dir(&self) -> &Path {
+		&self.common_dir
+	}
+
+	fn workdir(&self) -> Option<&Path> {
+		self.workdir.as_deref()
+	}
+
+	fn head_ref(&self) -> Result<Option<String>> {
+		Ok(
+			self
+				.git_optional(&["symbolic-ref", "--quiet", "HEAD"])?
+				.map(|name| name.trim_end().to_string()),
+		)
+	}
+
+	fn head_commit(&self) -> Result<backend::Commit> {
+		let commit = self.git(&["log", "-1", "--no-show-signature", COMMIT_FORMAT, "HEAD"])?;
+		parse_commit(&commit)
+	}
+
+	fn head_tags(&self) -> Result<Vec<String>> {
+		let tags = self.git(&[
+			"for-each-ref",
+			"--points-at",
+			"HEAD",
+			"--format=%(refname:strip=2)",
+			"refs/tags",
+		])?;
+		Ok(tags.lines().map(|tag| tag.to_string()).collect())
+	}
+
+	fn find_nearest_tag(&self, pattern: Option<&str>, accept: &dyn Fn(&str) -> bool) -> Result<Option<NearestTag>> {
+		find_nearest_tag(self, pattern, accept)
+	}
+
+	fn last_commit_touching(&self, path: &Path) -> Result<Option<backend::Commit>> {
+		let pathspec = match path.to_str() {
+			Some("") => ".",
+			Some(pathspec) => pathspec,
+			None => bail!("The path of the crate is not valid UTF-8"),
+		};
+		let commit = self.git(&[
+			"log",
+			"-1",
+			"--no-show-signature",
+			COMMIT_FORMAT,
+			"HEAD",
+			"--",
+			pathspec,
+		])?;
+		if commit.is_empty() {
+			return Ok(None);
+		}
+		parse_commit(&commit).map(Some)
+	}
+
+	fn upstream(&self, branch: &str) -> Result<Option<String>> {
+		let upstream = self.git(&["for-each-ref", "--format=%(upstream)", branch])?;
+		let upstream = upstream.trim_end();
+		if upstream.is_empty() {
+			return Ok(None);
+		}
+		let verify = format!("{}^{{commit}}", upstream);
+		if self
+			.git_optional(&["rev-parse", "--quiet", "--verify", &verify])?
+			.is_none()
+		{
+			return Ok(None);
+		}
+		Ok(Some(upstream.to_string()))
+	}
+
+	fn ahead_behind(&self, reference: &str) -> Result<(u32, u32)> {
+		let range = format!("HEAD...{}", reference);
+		let counts = self.git(&["rev-list", "--left-right", "--count", &range])?;
+		counts
+			.trim_end()
+			.split_once('\t')
+			.and_then(|(ahead, behind)| Some((ahead.parse().ok()?, behind.parse().ok()?)))
+			.ok_or_else(|| anyhow!("`git rev-list` returned invalid counts: {:?}", counts))
+	}
+
+	fn is_dirty(&self, pathspec: Option<&str>) -> Result<bool> {
+		is_dirty(self, pathspec, false)
+	}
+
+	fn dirty_tracking_paths(&self) -> Result<Vec<PathBuf>> {
+		dirty_tracking_paths(self)
+	}
+
+	fn config(&self, key: &str) -> Result<Option<String>> {
+		Ok(
+			self
+				.git_optional(&["config", key])?
+				.map(|value| value.trim_end().to_string()),
+		)
+	}
+
+	fn remotes(&self) -> Result<BTreeMap<String, String>> {
+		let mut remotes = BTreeMap::new();
+		for name in self.git(&["remote"])?.lines() {
+			if let Some(url) = self.git_optional(&["remote", "get-url", name])? {
+				remotes.insert(name.to_string(), url.trim_end().to_string());
+			}
+		}
+		Ok(remotes)
+	}
+
+	fn submodules(&self) -> Result<Vec<SubmoduleInfo>> {
+		submodules(self)
+	}
+}
+
+/// Parses the output of `git log` in the `COMMIT_FORMAT`
+fn parse_commit(commit: &str) -> Result<backend::Commit> {
+	let mut fields = commit.splitn(11, '\0');
+	let mut field = || {
+		fields
+			.next()
+			.ok_or_else(|| anyhow!("`git log` returned an incomplete commit"))
+	};
+	let id = field()?.to_string();
+	let short_id = field()?.to_string();
+	let time = field()?
+		.parse()
+		.map_err(|_| anyhow!("Encountered a commit with an invalid timestamp"))?;
+	let author = to_signature(field()?, field()?, field()?)?;
+	let committer = to_signature(field()?, field()?, field()?)?;
+	let summary = field()?.to_string();
+	let trailers = trailers(field()?);
+	Ok(backend::Commit {
+		id,
+		short_id,
+		time,
+		author,
+		committer,
+		summary: Some(summary),
+		trailers,
+	})
+}
+
+/// Converts a name, email address and strict ISO 8601 date of `git log` into a `Signature`
+fn to_signature(name: &str, email: &str, date: &str) -> Result<Signature> {
+	let date =
+		DateTime::parse_from_rfc3339(date).map_err(|_| anyhow!("Encountered a commit with an invalid timestamp"))?;
+	Ok(Signature {
+		name: Some(name.to_string()),
+		email: Some(email.to_string()),
+		time: date.timestamp(),
+		offset: date.offset().local_minus_utc(),
+	})
+}
+
+/// Parses trailers in the format of `%(trailers:only,unfold)`, grouping the values of trailers that occur multiple times
+fn trailers(lines: &str) -> BTreeMap<String, Vec<String>> {
+	let mut trailers: BTreeMap<String, Vec<String>> = BTreeMap::new();
+	for (key, value) in lines.lines().filter_map(|line| line.split_once(':')) {
+		trailers
+			.entry(key.trim().to_string())
+			.or_default()
+			.push(value.trim().to_string());
+	}
+	trailers
+}
+
+fn submodules(repository: &Repository) -> Result<Vec<SubmoduleInfo>> {
+	let workdir = match &repository.workdir {
+		Some(workdir) if workdir.join(".gitmodules").is_file() => workdir,
+		_ => return Ok(Vec::new()),
+	};
+	let paths = match repository.git_optional(&[
+		"config",
+		"--file",
+		".gitmodules",
+		"--null",
+		"--get-regexp",
+		r"^submodule\..*\.path$",
+	])? {
+		Some(paths) => paths,
+		None => return Ok(Vec::new()),
+	};
+
+	let mut submodules = Vec::new();
+	// With `--null`, every entry is terminated by a NUL byte, and the key is separated from the value by a newline
+	for path in paths
+		.split_terminator('\0')
+		.filter_map(|entry| entry.split_once('\n'))
+		.map(|(_, path)| path)
+	{
+		let checkout_dir = workdir.join(path);
+		let checkout = if checkout_dir.join(".git").exists() {
+			Repository::discover(&checkout_dir, None, None)?
+		} else {
+			None
+		};
+		let commit_id = match &checkout {
+			Some(checkout) => {
+				// Checking out a different commit in the submodule changes its HEAD
+				rerun_if_changed(&checkout.git_dir.join("HEAD"))?;
+				checkout
+					.git_optional(&["rev-parse", "--quiet", "--verify", "HEAD"])?
+					.map(|id| id.trim_end().to_string())
+			}
+			None => None,
+		};
+		let recorded_commit_id = repository
+			.git_optional(&["rev-parse", "--quiet", "--verify", &format!("HEAD:{}", path)])?
+			.map(|id| id.trim_end().to_string());
+		let dirty = match &checkout {
+			Some(checkout) => is_dirty(checkout, None, true)?,
+			None => false,
+		};
+
+		submodules.push(SubmoduleInfo {
+			path: path.to_string(),
+			matches_superproject: commit_id.is_some() && commit_id == recorded_commit_id,
+			commit_id,
+			recorded_commit_id,
+			dirty,
+		});
+	}
+	submodules.sort_by(|a, b| a.path.cmp(&b.path));
+	Ok(submodules)
+}
+
+/// Checks for changes to the index or the worktree, optionally restricted to `pathspec`.
+///
+/// Untracked files only count if `untracked` is set, which matches `git status --untracked-files=no` otherwise.
+fn is_dirty(repository: &Repository, pathspec: Option<&str>, untracked: bool) -> Result<bool> {
+	let mut args = vec![
+		"status",
+		"--porcelain",
+		if untracked {
+			"--untracked-files=normal"
+		} else {
+			"--untracked-files=no"
+		},
+	];
+	if let Some(pathspec) = pathspec {
+		args.extend(["--", pathspec]);
+	}
+	Ok(!repository.git(&args)?.is_empty())
+}
+
+/// The files that can change the result of `git status`: the index, the files it tracks and the untracked files.
+fn dirty_tracking_paths(repository: &Repository) -> Result<Vec<PathBuf>> {
+	let workdir = match &repository.workdir {
+		Some(workdir) => workdir,
+		None => return Ok(Vec::new()),
+	};
+	let mut paths = vec![repository.git_dir.join("index")];
+
+	// Each entry has the format `$mode $id $stage\t$path`
+	for entry in repository.git(&["ls-files", "--stage", "-z"])?.split_terminator('\0') {
+		let (metadata, path) = entry
+			.split_once('\t')
+			.ok_or_else(|| anyhow!("`git ls-files` returned an invalid entry: {:?}", entry))?;
+		// Submodules are recorded as gitlinks; they are directories that cargo would have to scan recursively
+		if metadata.starts_with("160000 ") {
+			continue;
+		}
+		paths.extend(tracked_path(workdir, path));
+	}
+
+	for path in repository
+		.git(&["ls-files", "--others", "--exclude-standard", "-z"])?
+		.split_terminator('\0')
+	{
+		paths.push(workdir.join(path));
+	}
+
+	paths.sort();
+	paths.dedup();
+	Ok(paths)
+}
+
+/// Finds the closest tag that is reachable from `HEAD` and matches `pattern` (if any), using
+/// `git describe --tags [--match $pattern]`.
+fn find_nearest_tag(
+	repository: &Repository,
+	pattern: Option<&str>,
+	accept: &dyn Fn(&str) -> bool,
+) -> Result<Option<NearestTag>> {
+	// Tags that are not accepted are excluded one by one, until the nearest remaining tag is accepted
+	let mut excluded: Vec<String> = Vec::new();
+	loop {
+		// Without a reachable tag, the description falls back to the bare commit hash, which is not parsed as a tag
+		let mut args = vec!["describe", "--tags", "--long", "--always"];
+		if let Some(pattern) = pattern {
+			args.extend(["--match", pattern]);
+		}
+		for tag in &excluded {
+			args.extend(["--exclude", tag.as_str()]);
+		}
+		match parse_long_describe(repository.git(&args)?.trim_end()) {
+			// Tag names cannot contain wildcards, so they are excluded verbatim
+			Some(tag) if !accept(&tag.name) => excluded.push(tag.name),
+			tag => return Ok(tag),
+		}
+	}
+}
+
+#[cfg(all(test, feature = "git"))]
+mod test {
+	use pretty_assertions::assert_eq;
+
+	use super::{super::backend_test, *};
+
+	#[test]
+	fn tags_and_crate_tags() {
+		backend_test::tags_and_crate_tags(get_info);
+	}
+
+	#[test]
+	fn version_tags() {
+		backend_test::version_tags(get_info);
+	}
+
+	#[test]
+	fn merge_history() {
+		backend_test::merge_history(get_info);
+	}
+
+	#[test]
+	fn dirty_worktree() {
+		backend_test::dirty_worktree(get_info);
+	}
+
+	#[test]
+	fn commit_metadata() {
+		backend_test::commit_metadata(get_info);
+	}
+
+	#[test]
+	fn remotes_and_upstream() {
+		backend_test::remotes_and_upstream(get_info);
+	}
+
+	#[test]
+	fn detached_head() {
+		backend_test::detached_head(get_info);
+	}
+
+	#[test]
+	fn submodules() {
+		backend_test::submodules(get_info);
+	}
+
+	#[test]
+	fn linked_worktree() {
+		backend_test::linked_worktree(get_info);
+	}
+
+	#[test]
+	fn git_dir_and_work_tree() {
+		backend_test::git_dir_and_work_tree(get_info);
+	}
+
+	#[test]
+	fn bare_repository() {
+		backend_test::bare_repository(get_info);
+	}
+
+	#[test]
+	#[cfg(unix)]
+	fn dubious_ownership() {
+		backend_test::dubious_ownership(get_info);
+	}
+
+	#[test]
+	fn unfolded_trailers() {
+		assert_eq!(
+			trailers("Reviewed-by: A <a@example.com>\nChange-Id: I1234\nReviewed-by: B <b@example.com>\n"),
+			[
+				("Change-Id".to_string(), vec!["I1234".to_string()]),
+				(
+					"Reviewed-by".to_string(),
+					vec!["A <a@example.com>".to_string(), "B <b@example.com>".to_string()]
+				),
+			]
+			.into_iter()
+			.collect::<BTreeMap<_, _>>()
+		);
+	}
+}
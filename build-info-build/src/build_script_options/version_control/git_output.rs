@@ -0,0 +1,60 @@
+use std::path::PathBuf;
+
+use super::describe::NearestTag;
+
+/// Parses the long `git describe` format `$tag-$distance-g$hash`; the tag itself may contain dashes.
+pub(crate) fn parse_long_describe(long: &str) -> Option<NearestTag> {
+	let mut parts = long.rsplitn(3, '-');
+	let suffix = parts.next()?;
+	let distance = parts.next()?.parse().ok()?;
+	let name = parts.next()?;
+	if !suffix.starts_with('g') {
+		return None;
+	}
+	Some(NearestTag {
+		name: name.to_string(),
+		distance,
+		suffix: suffix.to_string(),
+	})
+}
+
+/// Extracts the first path in single quotes from an error message of git, e.g., the repository in
+/// `detected dubious ownership in repository at '/src'`
+pub(crate) fn quoted_path(message: &str) -> Option<PathBuf> {
+	let (_, rest) = message.split_once('\'')?;
+	let (path, _) = rest.split_once('\'')?;
+	Some(PathBuf::from(path))
+}
+
+#[cfg(test)]
+mod test {
+	use pretty_assertions::assert_eq;
+
+	use super::*;
+
+	#[test]
+	fn parse_describe() {
+		assert_eq!(
+			parse_long_describe("sample@v1.4.2-rc.1-17-gabc1234"),
+			Some(NearestTag {
+				name: "sample@v1.4.2-rc.1".to_string(),
+				distance: 17,
+				suffix: "gabc1234".to_string(),
+			})
+		);
+		assert_eq!(parse_long_describe("abc1234"), None);
+	}
+
+	#[test]
+	fn path_from_message() {
+		assert_eq!(
+			quoted_path("fatal: detected dubious ownership in repository at '/src'\nTo add an exception..."),
+			Some(PathBuf::from("/src"))
+		);
+		assert_eq!(
+			quoted_path("repository path '/src/' is not owned by current user"),
+			Some(PathBuf::from("/src/"))
+		);
+		assert_eq!(quoted_path("not owned by current user"), None);
+	}
+}
@@ -0,0 +1,449 @@
+use std::{
+	collections::BTreeMap,
+	path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Result};
+use build_info_common::{GitInfo, SubmoduleInfo};
+use gix::{
+	actor::SignatureRef,
+	bstr::{BStr, BString, ByteSlice},
+	remote::Direction,
+	status::{index_worktree, plumbing::index_as_worktree::EntryStatus, UntrackedFiles},
+	Commit, ObjectId, Repository,
+};
+
+use super::{
+	super::rerun_if_changed,
+	backend::{self, git_info, tracked_path, GitBackend, Signature},
+	describe::NearestTag,
+	discovery::warn_dubious_ownership,
+	history::follow_history,
+	CrateContext, VersionControlOptions,
+};
+
+/// Returns `None` if the crate is not part of a git repository, or if the repository belongs to a different user.
+pub(crate) fn get_info(krate: &CrateContext, options: &VersionControlOptions) -> Result<Option<GitInfo>> {
+	match open(krate)? {
+		Some(repository) => Ok(Some(git_info(&repository, krate, options)?)),
+		None => Ok(None),
+	}
+}
+
+impl GitBackend for Repository {
+	fn git_dir(&self) -> &Path {
+		Repository::git_dir(self)
+	}
+
+	fn common_dir(&self) -> &Path {
+		Repository::common_dir(self)
+	}
+
+	fn workdir(&self) -> Option<&Path> {
+		Repository::workdir(self)
+	}
+
+	fn head_ref(&self) -> Result<Option<String>> {
+		self
+			.head()?
+			.referent_name()
+			.map(|name| to_string(name.as_bstr()))
+			.transpose()
+	}
+
+	fn head_commit(&self) -> Result<backend::Commit> {
+		to_commit(&Repository::head_commit(self)?)
+	}
+
+	fn head_tags(&self) -> Result<Vec<String>> {
+		tags(self, Repository::head_commit(self)?.id)
+	}
+
+	fn find_nearest_tag(&self, _pattern: Option<&str>, accept: &dyn Fn(&str) -> bool) -> Result<Option<NearestTag>> {
+		find_nearest_tag(self, accept)
+	}
+
+	fn last_commit_touching(&self, path: &Path) -> Result<Option<backend::Commit>> {
+		let commit = follow_history(
+			Repository::head_commit(self)?,
+			path,
+			|commit| {
+				commit
+					.parent_ids()
+					.map(|parent_id| Ok(self.find_commit(parent_id)?))
+					.collect()
+			},
+			|commit: &Commit, path| {
+				let tree = commit.tree()?;
+				if path.as_os_str().is_empty() {
+					return Ok(Some(tree.id));
+				}
+				Ok(tree.lookup_entry_by_path(path)?.map(|entry| entry.object_id()))
+			},
+		)?;
+		commit.as_ref().map(to_commit).transpose()
+	}
+
+	fn upstream(&self, branch: &str) -> Result<Option<String>> {
+		let branch: &gix::refs::FullNameRef = branch.try_into()?;
+		let tracking_branch = match self.branch_remote_tracking_ref_name(branch, Direction::Fetch) {
+			Some(tracking_branch) => tracking_branch?,
+			None => return Ok(None),
+		};
+		match self.try_find_reference(tracking_branch.as_ref())? {
+			Some(upstream) => Ok(Some(to_string(upstream.name().as_bstr())?)),
+			None => Ok(None),
+		}
+	}
+
+	fn ahead_behind(&self, reference: &str) -> Result<(u32, u32)> {
+		let head = Repository::head_commit(self)?.id;
+		let upstream = self.find_reference(reference)?.peel_to_commit()?.id;
+		let count = |tip: ObjectId, hidden: ObjectId| -> Result<u32> {
+			let mut count = 0u32;
+			for commit in self.rev_walk([tip]).with_hidden([hidden]).all()? {
+				commit?;
+				count += 1;
+			}
+			Ok(count)
+		};
+		Ok((count(head, upstream)?, count(upstream, head)?))
+	}
+
+	fn is_dirty(&self, pathspec: Option<&str>) -> Result<bool> {
+		is_dirty(self, pathspec, false)
+	}
+
+	fn dirty_tracking_paths(&self) -> Result<Vec<PathBuf>> {
+		dirty_tracking_paths(self)
+	}
+
+	fn config(&self, key: &str) -> Result<Option<String>> {
+		self
+			.config_snapshot()
+			.string(key)
+			.map(|value| to_string(value.as_ref()))
+			.transpose()
+	}
+
+	fn remotes(&self) -> Result<BTreeMap<String, String>> {
+		let mut remotes = BTreeMap::new();
+		for name in self.remote_names() {
+			let remote = self.find_remote(name.as_ref())?;
+			if let Some(url) = remote.url(Direction::Fetch) {
+				if let Ok(url) = url.to_bstring().to_str() {
+					remotes.insert(to_string(name.as_ref())?, url.to_string());
+				}
+			}
+		}
+		Ok(remotes)
+	}
+
+	fn submodules(&self) -> Result<Vec<SubmoduleInfo>> {
+		submodules(self)
+	}
+}
+
+fn to_string(value: &BStr) -> Result<String> {
+	Ok(value.to_str()?.to_string())
+}
+
+fn to_commit(commit: &Commit) -> Result<backend::Commit> {
+	let message = commit.message()?;
+	let mut trailers: BTreeMap<String, Vec<String>> = BTreeMap::new();
+	for trailer in message.body().iter().flat_map(|body| body.trailers()) {
+		trailers
+			.entry(to_string(trailer.token)?)
+			.or_default()
+			.push(to_string(trailer.value)?);
+	}
+	Ok(backend::Commit {
+		id: commit.id.to_string(),
+		short_id: commit.id().shorten()?.to_string(),
+		time: commit.time()?.seconds,
+		author: to_signature(commit.author()?.trim())?,
+		committer: to_signature(commit.committer()?.trim())?,
+		summary: message.summary().to_str().ok().map(|summary| summary.to_string()),
+		trailers,
+	})
+}
+
+fn to_signature(signature: SignatureRef) -> Result<Signature> {
+	let time = signature.time()?;
+	Ok(Signature {
+		name: signature.name.to_str().ok().map(|name| name.to_string()),
+		email: signature.email.to_str().ok().map(|email| email.to_string()),
+		time: time.seconds,
+		offset: time.offset,
+	})
+}
+
+/// Opens the repository given by `GIT_DIR`, or discovers the repository that contains the crate
+fn open(krate: &CrateContext) -> Result<Option<Repository>> {
+	// Like git, refuse repositories of other users unless they are listed in `safe.directory`
+	let options = gix::open::Options::default().bail_if_untrusted(true);
+	let mut repository = match &krate.git.git_dir {
+		Some(git_dir) => match gix::ThreadSafeRepository::open_opts(git_dir, options) {
+			Ok(repository) => repository,
+			Err(gix::open::Error::UnsafeGitDir { path }) => {
+				warn_dubious_ownership(&path);
+				return Ok(None);
+			}
+			Err(err) => return Err(err.into()),
+		},
+		None => {
+			let trust_map = gix::sec::trust::Mapping {
+				full: options.clone(),
+				reduced: options,
+			};
+			match gix::ThreadSafeRepository::discover_opts(&krate.manifest_dir, Default::default(), trust_map) {
+				Ok(repository) => repository,
+				Err(gix::discover::Error::Discover(
+					gix::discover::upwards::Error::NoGitRepository { .. }
+					| gix::discover::upwards::Error::NoGitRepositoryWithinCeiling { .. }
+					| gix::discover::upwards::Error::NoGitRepositoryWithinFs { .. },
+				)) => return Ok(None),
+				Err(gix::discover::Error::Open(gix::open::Error::UnsafeGitDir { path })) => {
+					warn_dubious_ownership(&path);
+					return Ok(None);
+				}
+				Err(err) => return Err(err.into()),
+			}
+		}
+	};
+	if let Some(work_tree) = &krate.git.git_work_tree {
+		repository.work_tree = Some(work_tree.clone());
+	}
+	Ok(Some(repository.to_thread_local()))
+}
+
+fn submodules(repository: &Repository) -> Result<Vec<SubmoduleInfo>> {
+	let mut submodules = Vec::new();
+	if repository.is_bare() {
+		return Ok(submodules);
+	}
+	for submodule in repository.submodules()?.into_iter().flatten() {
+		let path = to_string(submodule.path()?.as_ref())?;
+
+		let checkout = submodule.open()?;
+		let commit_id = match &checkout {
+			Some(checkout) => {
+				// Checking out a different commit in the submodule changes its HEAD
+				rerun_if_changed(&checkout.git_dir().join("HEAD"))?;
+				checkout.head()?.id().map(|id| id.detach())
+			}
+			None => None,
+		};
+		let recorded_commit_id = submodule.head_id()?;
+		let dirty = match &checkout {
+			Some(checkout) => is_dirty(checkout, None, true)?,
+			None => false,
+		};
+
+		submodules.push(SubmoduleInfo {
+			path,
+			commit_id: commit_id.map(|id| id.to_string()),
+			recorded_commit_id: recorded_commit_id.map(|id| id.to_string()),
+			matches_superproject: commit_id.is_some() && commit_id == recorded_commit_id,
+			dirty,
+		});
+	}
+	submodules.sort_by(|a, b| a.path.cmp(&b.path));
+	Ok(submodules)
+}
+
+/// Checks for changes to the index or the worktree, optionally restricted to `pathspec`.
+///
+/// Untracked files only count if `untracked` is set, which matches `git status --untracked-files=no` otherwise.
+fn is_dirty(repository: &Repository, pathspec: Option<&str>, untracked: bool) -> Result<bool> {
+	let untracked = if untracked {
+		UntrackedFiles::Collapsed
+	} else {
+		UntrackedFiles::None
+	};
+	let status = repository
+		.status(gix::progress::Discard)?
+		.untracked_files(untracked)
+		.into_iter(pathspec.map(BString::from))?;
+	for item in status {
+		let changed = match item? {
+			// Only the file metadata differs from the index, which `git status` does not report
+			gix::status::Item::IndexWorktree(index_worktree::Item::Modification {
+				status: EntryStatus::NeedsUpdate(_),
+				..
+			}) => false,
+			gix::status::Item::IndexWorktree(index_worktree::Item::DirectoryContents { entry, .. }) => {
+				entry.status == gix::dir::entry::Status::Untracked
+			}
+			_ => true,
+		};
+		if changed {
+			return Ok(true);
+		}
+	}
+	Ok(false)
+}
+
+/// The files that can change the result of `git status`: the index, the files it tracks and the untracked files.
+fn dirty_tracking_paths(repository: &Repository) -> Result<Vec<PathBuf>> {
+	let mut paths = vec![repository.git_dir().join("index")];
+
+	if let Some(workdir) = repository.workdir() {
+		let index = repository.index_or_empty()?;
+		for entry in index.entries() {
+			// Submodules are recorded as gitlinks; they are directories that cargo would have to scan recursively
+			if entry.mode == gix::index::entry::Mode::COMMIT {
+				continue;
+			}
+			let path = entry
+				.path(&index)
+				.to_str()
+				.map_err(|_| anyhow!("Encountered a tracked path that is not valid UTF-8"))?;
+			paths.extend(tracked_path(workdir, path));
+		}
+
+		let untracked = repository
+			.status(gix::progress::Discard)?
+			.untracked_files(UntrackedFiles::Files)
+			.into_index_worktree_iter(Vec::<BString>::new())?;
+		for item in untracked {
+			if let index_worktree::Item::DirectoryContents { entry, .. } = item? {
+				if entry.status == gix::dir::entry::Status::Untracked {
+					let path = entry
+						.rela_path
+						.to_str()
+						.map_err(|_| anyhow!("Encountered an untracked path that is not valid UTF-8"))?;
+					paths.push(workdir.join(path));
+				}
+			}
+		}
+	}
+
+	paths.sort();
+	paths.dedup();
+	Ok(paths)
+}
+
+/// Finds the closest tag that is reachable from `HEAD` and is accepted by `accept`, the same way `git describe --tags`
+/// does.
+fn find_nearest_tag(repository: &Repository, accept: &dyn Fn(&str) -> bool) -> Result<Option<NearestTag>> {
+	let head = repository.head_commit()?;
+	// If multiple tags point to the same commit, annotated tags are preferred over lightweight ones, and newer tags over
+	// older ones
+	let mut candidates = Vec::new();
+	for reference in repository.references()?.tags()? {
+		let mut reference = reference.map_err(|err| anyhow!(err))?;
+		let name = to_string(reference.name().shorten())?;
+		if !accept(&name) {
+			continue;
+		}
+		let tag_time = match reference.try_id().and_then(|id| id.object().ok()?.try_into_tag().ok()) {
+			Some(tag) => Some(tag.tagger()?.map(|tagger| tagger.seconds()).unwrap_or_default()),
+			None => None,
+		};
+		if let Ok(commit) = reference.peel_to_commit() {
+			candidates.push((commit.id, tag_time, name));
+		}
+	}
+	candidates.sort_by(|(_, a_time, a_name), (_, b_time, b_name)| a_time.cmp(b_time).then_with(|| b_name.cmp(a_name)));
+	let name_by_oid: gix::hashtable::HashMap<_, _> = candidates
+		.into_iter()
+		.map(|(id, _, name)| (id, BString::from(name).into()))
+		.collect();
+
+	let cache = repository.commit_graph_if_enabled()?;
+	let mut graph = repository.revision_graph(cache.as_ref());
+	let outcome = gix::revision::plumbing::describe(
+		&head.id,
+		&mut graph,
+		gix::revision::plumbing::describe::Options {
+			name_by_oid,
+			..Default::default()
+		},
+	)?;
+	Ok(outcome.and_then(|outcome| {
+		Some(NearestTag {
+			name: outcome.name?.to_str().ok()?.to_string(),
+			distance: outcome.depth,
+			suffix: format!("g{}", head.id().shorten().ok()?),
+		})
+	}))
+}
+
+fn tags(repository: &Repository, commit_id: ObjectId) -> Result<Vec<String>> {
+	let mut result = Vec::new();
+	for reference in repository.references()?.tags()? {
+		let mut reference = reference.map_err(|err| anyhow!(err))?;
+		if reference.peel_to_commit()?.id == commit_id {
+			result.push(to_string(reference.name().shorten())?);
+		}
+	}
+	result.sort();
+	Ok(result)
+}
+
+#[cfg(all(test, feature = "git"))]
+mod test {
+	use super::{super::backend_test, get_info};
+
+	#[test]
+	fn tags_and_crate_tags() {
+		backend_test::tags_and_crate_tags(get_info);
+	}
+
+	#[test]
+	fn version_tags() {
+		backend_test::version_tags(get_info);
+	}
+
+	#[test]
+	fn merge_history() {
+		backend_test::merge_history(get_info);
+	}
+
+	#[test]
+	fn dirty_worktree() {
+		backend_test::dirty_worktree(get_info);
+	}
+
+	#[test]
+	fn commit_metadata() {
+		backend_test::commit_metadata(get_info);
+	}
+
+	#[test]
+	fn remotes_and_upstream() {
+		backend_test::remotes_and_upstream(get_info);
+	}
+
+	#[test]
+	fn detached_head() {
+		backend_test::detached_head(get_info);
+	}
+
+	#[test]
+	fn submodules() {
+		backend_test::submodules(get_info);
+	}
+
+	#[test]
+	fn linked_worktree() {
+		backend_test::linked_worktree(get_info);
+	}
+
+	#[test]
+	fn git_dir_and_work_tree() {
+		backend_test::git_dir_and_work_tree(get_info);
+	}
+
+	#[test]
+	fn bare_repository() {
+		backend_test::bare_repository(get_info);
+	}
+
+	#[test]
+	#[cfg(unix)]
+	fn dubious_ownership() {
+		backend_test::dubious_ownership(get_info);
+	}
+}
@@ -0,0 +1,45 @@
+use std::path::Path;
+
+use anyhow::Result;
+
+/// Finds the last commit reachable from `head` that changed `path`, following the same history simplification as
+/// `git log -1 -- $path`: at a merge commit that has a parent with the same content at `path`, only that parent is
+/// followed, so the history is walked along a single line of commits until one differs from all of its parents.
+///
+/// `entry_id` looks up the object id of a path in the tree of a commit; the empty path denotes the tree itself.
+pub(crate) fn follow_history<C, Id: PartialEq>(
+	head: C,
+	path: &Path,
+	parents: impl Fn(&C) -> Result<Vec<C>>,
+	entry_id: impl Fn(&C, &Path) -> Result<Option<Id>>,
+) -> Result<Option<C>> {
+	let root = Path::new("");
+	let mut commit = head;
+	// The entry of the current commit is carried over to its parent, which has the same entry
+	let mut entry = None;
+	loop {
+		let parents = parents(&commit)?;
+		let is_root = parents.is_empty();
+		let tree = entry_id(&commit, root)?;
+		let mut treesame_parent = None;
+		for parent in parents {
+			// Identical trees need no lookup of the entry
+			let treesame = entry_id(&parent, root)? == tree || {
+				if entry.is_none() {
+					entry = Some(entry_id(&commit, path)?);
+				}
+				entry_id(&parent, path)? == *entry.as_ref().unwrap()
+			};
+			if treesame {
+				treesame_parent = Some(parent);
+				break;
+			}
+		}
+		commit = match treesame_parent {
+			Some(parent) => parent,
+			// A root commit only changes `path` if it contains it
+			None if is_root && entry_id(&commit, path)?.is_none() => return Ok(None),
+			None => return Ok(Some(commit)),
+		};
+	}
+}
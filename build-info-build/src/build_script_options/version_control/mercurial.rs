@@ -0,0 +1,157 @@
+use std::{
+	path::{Path, PathBuf},
+	process::Command,
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use build_info_common::MercurialInfo;
+
+use super::{super::rerun_if_changed, CrateContext};
+
+/// Returns `None` if the crate is not part of a Mercurial repository.
+pub(crate) fn get_info(krate: &CrateContext) -> Result<Option<MercurialInfo>> {
+	let root = match find_root(&krate.manifest_dir) {
+		Some(root) => root,
+		None => return Ok(None),
+	};
+
+	// The dirstate records the parent of the working directory, the other files contain branch, bookmarks and tags
+	let hg_dir = root.join(".hg");
+	for path in [
+		hg_dir.join("dirstate"),
+		hg_dir.join("branch"),
+		hg_dir.join("bookmarks"),
+		hg_dir.join("localtags"),
+		root.join(".hgtags"),
+	] {
+		if path.exists() {
+			rerun_if_changed(&path)?;
+		}
+	}
+
+	let mut info = parse_log(&hg(&root, &["log", "--rev", ".", "--template", "json"])?)?;
+	info.dirty = !hg(&root, &["status", "--modified", "--added", "--removed", "--deleted"])?
+		.trim()
+		.is_empty();
+	Ok(Some(info))
+}
+
+/// Finds the innermost directory that contains a `.hg` directory
+fn find_root(dir: &Path) -> Option<PathBuf> {
+	dir
+		.ancestors()
+		.find(|dir| dir.join(".hg").is_dir())
+		.map(Path::to_path_buf)
+}
+
+fn hg(root: &Path, args: &[&str]) -> Result<String> {
+	let output = Command::new("hg")
+		.args(args)
+		.current_dir(root)
+		// Ignore user configuration that changes the output, such as aliases or localization
+		.env("HGPLAIN", "1")
+		.output()
+		.context("Could not run `hg`")?;
+	if !output.status.success() {
+		bail!(
+			"`hg {}` failed: {}",
+			args.join(" "),
+			String::from_utf8_lossy(&output.stderr).trim()
+		);
+	}
+	Ok(String::from_utf8(output.stdout)?)
+}
+
+/// Parses the output of `hg log --rev . --template json`; the `dirty` flag is not part of it
+fn parse_log(log: &str) -> Result<MercurialInfo> {
+	let log: serde_json::Value = serde_json::from_str(log)?;
+	let changeset = log
+		.get(0)
+		.ok_or_else(|| anyhow!("`hg log` did not return the parent of the working directory"))?;
+
+	let string = |key: &str| {
+		changeset[key]
+			.as_str()
+			.map(|value| value.to_string())
+			.ok_or_else(|| anyhow!("`hg log` returned an invalid `{}`", key))
+	};
+	let strings = |key: &str| {
+		changeset[key]
+			.as_array()
+			.and_then(|values| {
+				values
+					.iter()
+					.map(|value| value.as_str().map(|value| value.to_string()))
+					.collect::<Option<Vec<_>>>()
+			})
+			.ok_or_else(|| anyhow!("`hg log` returned invalid `{}`", key))
+	};
+
+	let revision = changeset["rev"]
+		.as_i64()
+		.ok_or_else(|| anyhow!("`hg log` returned an invalid `rev`"))?;
+	if revision < 0 {
+		bail!("The Mercurial repository does not contain any changesets");
+	}
+
+	Ok(MercurialInfo {
+		changeset_id: string("node")?,
+		revision: revision.try_into()?,
+		branch: string("branch")?,
+		bookmarks: strings("bookmarks")?,
+		tags: strings("tags")?.into_iter().filter(|tag| tag != "tip").collect(),
+		dirty: false,
+	})
+}
+
+#[cfg(test)]
+mod test {
+	use pretty_assertions::assert_eq;
+
+	use super::*;
+
+	#[test]
+	fn parse_changeset() {
+		let log = r#"[
+ {
+  "bookmarks": ["feature"],
+  "branch": "stable",
+  "date": [1591113000, -7200],
+  "desc": "Release 1.4.2",
+  "node": "0b4a9fd7d3e4b1c4d1b7a1f5bf3c4e0d7cc1e3a2",
+  "parents": ["7e3f1d0c9b6a5f4e3d2c1b0a9f8e7d6c5b4a3f2e"],
+  "phase": "public",
+  "rev": 17,
+  "tags": ["v1.4.2", "tip"],
+  "user": "Test <test@example.com>"
+ }
+]"#;
+		assert_eq!(
+			parse_log(log).unwrap(),
+			MercurialInfo {
+				changeset_id: "0b4a9fd7d3e4b1c4d1b7a1f5bf3c4e0d7cc1e3a2".to_string(),
+				revision: 17,
+				branch: "stable".to_string(),
+				bookmarks: vec!["feature".to_string()],
+				tags: vec!["v1.4.2".to_string()],
+				dirty: false,
+			}
+		);
+	}
+
+	#[test]
+	fn empty_repository() {
+		let log = r#"[{"bookmarks": [], "branch": "default", "node": "0000000000000000000000000000000000000000", "rev": -1, "tags": ["tip"]}]"#;
+		assert!(parse_log(log).is_err());
+	}
+
+	#[test]
+	fn root_is_innermost_repository() {
+		let dir = tempfile::tempdir().unwrap();
+		let nested = dir.path().join("vendor/library");
+		std::fs::create_dir_all(nested.join(".hg")).unwrap();
+		std::fs::create_dir_all(dir.path().join(".hg")).unwrap();
+		assert_eq!(find_root(&nested.join("src")), Some(nested));
+		assert_eq!(find_root(&dir.path().join("src")), Some(dir.path().to_path_buf()));
+	}
+}
@@ -1,24 +1,307 @@
+use std::path::PathBuf;
+
 use build_info_common::VersionControl;
 
+use crate::BuildInfoError;
+
+#[cfg(any(feature = "git", feature = "gix", feature = "git-cli"))]
+mod backend;
+#[cfg(all(test, feature = "git", any(feature = "gix", feature = "git-cli")))]
+mod backend_test;
+mod cargo_vcs_info;
+#[cfg(any(feature = "git", feature = "gix", feature = "git-cli"))]
+mod crate_tag;
+#[cfg(any(feature = "git", feature = "gix", feature = "git-cli"))]
+mod describe;
+#[cfg(any(feature = "git", feature = "gix", feature = "git-cli"))]
+mod discovery;
+// `gix` takes precedence over `git2`, which is enabled by default; `git-cli` is only a fallback for builds without either
 #[cfg(feature = "git")]
+#[cfg_attr(feature = "gix", allow(dead_code))]
 mod git;
+#[cfg(feature = "git-cli")]
+#[cfg_attr(any(feature = "git", feature = "gix"), allow(dead_code))]
+mod git_cli;
+// Parsers for the output of `git` and libgit2
+#[cfg(any(feature = "git", feature = "git-cli"))]
+mod git_output;
+#[cfg(feature = "gix")]
+mod gitoxide;
+// The library backends walk the history themselves, whereas `git-cli` relies on `git log`
+#[cfg(any(feature = "git", feature = "gix"))]
+mod history;
+#[cfg(feature = "mercurial")]
+mod mercurial;
+#[cfg(any(feature = "git", feature = "gix", feature = "git-cli"))]
+mod remote;
+#[cfg(feature = "subversion")]
+mod subversion;
 
-#[cfg(feature = "git")]
-fn get_git_info() -> anyhow::Result<VersionControl> {
-	git::get_info().map(VersionControl::Git)
+/// The crate whose version control information is collected
+pub(crate) struct CrateContext {
+	/// The directory that contains the manifest of the crate, from which the repository is discovered
+	pub manifest_dir: PathBuf,
+
+	/// The name and version of the crate, and the repository given by the environment
+	#[cfg(any(feature = "git", feature = "gix", feature = "git-cli"))]
+	pub git: backend::GitContext,
 }
 
-#[cfg(not(feature = "git"))]
-fn get_git_info() -> anyhow::Result<VersionControl> {
-	Err(anyhow::anyhow!("Git support is disabled"))
+impl CrateContext {
+	fn from_env() -> Result<Self, BuildInfoError> {
+		let manifest_dir = std::env::var_os("CARGO_MANIFEST_DIR").ok_or_else(|| BuildInfoError::MissingEnvVar {
+			name: "CARGO_MANIFEST_DIR".to_string(),
+		})?;
+		Ok(Self {
+			manifest_dir: manifest_dir.into(),
+			#[cfg(any(feature = "git", feature = "gix", feature = "git-cli"))]
+			git: backend::GitContext::from_env()?,
+		})
+	}
+}
+
+/// The default pattern for tags that belong to a single crate of a repository, e.g., `sample@v0.0.10`
+const DEFAULT_CRATE_TAG_PATTERN: &str = "{crate}@v{version}";
+
+impl crate::BuildScriptOptions {
+	/// Sets the pattern of the tags that belong to the current crate (default: `"{crate}@v{version}"`).
+	///
+	/// This is useful for repositories that contain multiple crates, which are released independently. `{crate}` is
+	/// replaced with the name of the crate, and the version is parsed from the part of the tag that matches `{version}`.
+	/// Tags matching the pattern are reported in the `crate_*` fields of `GitInfo`, which also records whether the
+	/// version of the tag agrees with the version in `Cargo.toml`.
+	///
+	/// The pattern must contain `{version}` exactly once, otherwise the build script fails with
+	/// [`BuildInfoError::InvalidCrateTagPattern`].
+	pub fn crate_tag_pattern(mut self, pattern: impl Into<String>) -> Self {
+		self.version_control.crate_tag_pattern = pattern.into();
+		self
+	}
 }
 
-pub(crate) fn get_info() -> Option<VersionControl> {
-	if cfg!(feature = "git") {
-		if let Ok(info) = get_git_info() {
-			return Some(info);
+/// Checks that `pattern` contains `{version}` exactly once
+fn check_crate_tag_pattern(pattern: &str) -> Result<(), BuildInfoError> {
+	if pattern.matches("{version}").count() != 1 {
+		return Err(BuildInfoError::InvalidCrateTagPattern {
+			pattern: pattern.to_string(),
+		});
+	}
+	Ok(())
+}
+
+/// Options that control how version control information is collected
+pub(crate) struct VersionControlOptions {
+	/// Rerun the build script whenever the index or a file in the worktree changes
+	pub track_dirty: bool,
+
+	/// The pattern of tags that belong to the current crate
+	pub crate_tag_pattern: String,
+
+	/// Collect the names and email addresses of the author and committer of the current commit
+	pub commit_identities: bool,
+
+	/// Collect the author and commit timestamps of the current commit, including their UTC offsets
+	pub commit_timestamps: bool,
+
+	/// Collect the first line of the commit message
+	pub commit_summary: bool,
+
+	/// Collect the trailers of the commit message
+	pub commit_trailers: bool,
+}
+
+impl VersionControlOptions {
+	/// Checks the options before any information is collected
+	pub(crate) fn check(&self) -> Result<(), BuildInfoError> {
+		check_crate_tag_pattern(&self.crate_tag_pattern)
+	}
+}
+
+impl Default for VersionControlOptions {
+	fn default() -> Self {
+		Self {
+			track_dirty: false,
+			crate_tag_pattern: DEFAULT_CRATE_TAG_PATTERN.to_string(),
+			commit_identities: false,
+			commit_timestamps: false,
+			commit_summary: false,
+			commit_trailers: false,
 		}
 	}
+}
+
+impl crate::BuildScriptOptions {
+	/// Keeps the `dirty` flag of the version control information accurate (disabled by default).
+	///
+	/// By default, the build script is only rerun when the checked out commit or the crate itself changes, so changes to
+	/// other files of the repository (e.g., a `README.md`) as well as staging or stashing changes may leave the `dirty`
+	/// flag stale. With this option enabled, the build script also depends on the git index and on all tracked and
+	/// untracked (but not ignored) files in the worktree. Note that creating a new file will still only be noticed once
+	/// the build script runs for another reason, or the file is added to the index.
+	///
+	/// This emits a `cargo:rerun-if-changed` line for every file in the worktree, which cargo has to check before each
+	/// build. In large repositories, this amounts to thousands of lines and a noticeable delay for every build.
+	pub fn track_dirty_worktree(mut self, track_dirty: bool) -> Self {
+		self.version_control.track_dirty = track_dirty;
+		self
+	}
 
-	None
+	/// Enables and disables the collection of the names and email addresses of the author and committer of the current
+	/// commit (disabled by default).
+	///
+	/// This is personal data, so consider whether it should be part of your binary before enabling it.
+	pub fn collect_commit_identities(mut self, collect_commit_identities: bool) -> Self {
+		self.version_control.commit_identities = collect_commit_identities;
+		self
+	}
+
+	/// Enables and disables the collection of the author and commit timestamps of the current commit, including the UTC
+	/// offsets they were recorded with (disabled by default).
+	///
+	/// The UTC offsets may reveal where the author and committer are located.
+	pub fn collect_commit_timestamps(mut self, collect_commit_timestamps: bool) -> Self {
+		self.version_control.commit_timestamps = collect_commit_timestamps;
+		self
+	}
+
+	/// Enables and disables the collection of the first line of the message of the current commit (disabled by
+	/// default).
+	pub fn collect_commit_summary(mut self, collect_commit_summary: bool) -> Self {
+		self.version_control.commit_summary = collect_commit_summary;
+		self
+	}
+
+	/// Enables and disables the collection of the trailers of the message of the current commit, such as
+	/// `Reviewed-by:` or `Change-Id:` (disabled by default).
+	pub fn collect_commit_trailers(mut self, collect_commit_trailers: bool) -> Self {
+		self.version_control.commit_trailers = collect_commit_trailers;
+		self
+	}
+}
+
+#[cfg(all(feature = "git", not(feature = "gix")))]
+fn get_git_info(krate: &CrateContext, options: &VersionControlOptions) -> anyhow::Result<Option<VersionControl>> {
+	Ok(git::get_info(krate, options)?.map(VersionControl::Git))
+}
+
+#[cfg(feature = "gix")]
+fn get_git_info(krate: &CrateContext, options: &VersionControlOptions) -> anyhow::Result<Option<VersionControl>> {
+	Ok(gitoxide::get_info(krate, options)?.map(VersionControl::Git))
+}
+
+#[cfg(all(feature = "git-cli", not(any(feature = "git", feature = "gix"))))]
+fn get_git_info(krate: &CrateContext, options: &VersionControlOptions) -> anyhow::Result<Option<VersionControl>> {
+	Ok(git_cli::get_info(krate, options)?.map(VersionControl::Git))
+}
+
+#[cfg(not(any(feature = "git", feature = "gix", feature = "git-cli")))]
+fn get_git_info(_krate: &CrateContext, _options: &VersionControlOptions) -> anyhow::Result<Option<VersionControl>> {
+	Ok(None)
+}
+
+#[cfg(feature = "mercurial")]
+fn get_mercurial_info(krate: &CrateContext) -> anyhow::Result<Option<VersionControl>> {
+	Ok(mercurial::get_info(krate)?.map(VersionControl::Mercurial))
+}
+
+#[cfg(not(feature = "mercurial"))]
+fn get_mercurial_info(_krate: &CrateContext) -> anyhow::Result<Option<VersionControl>> {
+	Ok(None)
+}
+
+#[cfg(feature = "subversion")]
+fn get_subversion_info(krate: &CrateContext) -> anyhow::Result<Option<VersionControl>> {
+	Ok(subversion::get_info(krate)?.map(VersionControl::Subversion))
+}
+
+#[cfg(not(feature = "subversion"))]
+fn get_subversion_info(_krate: &CrateContext) -> anyhow::Result<Option<VersionControl>> {
+	Ok(None)
+}
+
+/// Collects information about the version control system, if the crate is part of a repository.
+///
+/// A crate that was packaged by `cargo package` records its commit in `.cargo_vcs_info.json`, which takes precedence:
+/// packaged crates (e.g., in `target/package` or in the registry cache) may well be located inside an unrelated
+/// repository. Otherwise, the version control systems are tried in order (git, Mercurial, Subversion), and the first one
+/// that finds a repository is used.
+pub(crate) fn get_info(options: &VersionControlOptions) -> Result<Option<VersionControl>, BuildInfoError> {
+	collect(&CrateContext::from_env()?, options)
+}
+
+fn collect(krate: &CrateContext, options: &VersionControlOptions) -> Result<Option<VersionControl>, BuildInfoError> {
+	if let Some(info) = cargo_vcs_info::get_info(&krate.manifest_dir).map_err(BuildInfoError::VersionControl)? {
+		return Ok(Some(VersionControl::CargoVcsInfo(info)));
+	}
+	let info = match get_git_info(krate, options).map_err(BuildInfoError::VersionControl)? {
+		Some(info) => Some(info),
+		None => get_mercurial_info(krate).map_err(BuildInfoError::VersionControl)?,
+	};
+	match info {
+		Some(info) => Ok(Some(info)),
+		None => get_subversion_info(krate).map_err(BuildInfoError::VersionControl),
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use pretty_assertions::assert_eq;
+
+	use super::*;
+
+	#[test]
+	fn crate_tag_patterns() {
+		assert!(matches!(
+			check_crate_tag_pattern("{crate}"),
+			Err(BuildInfoError::InvalidCrateTagPattern { pattern }) if pattern == "{crate}"
+		));
+		assert!(check_crate_tag_pattern("{crate}@v{version}-{version}").is_err());
+		assert_eq!(check_crate_tag_pattern(DEFAULT_CRATE_TAG_PATTERN).ok(), Some(()));
+	}
+
+	#[test]
+	#[cfg(feature = "git")]
+	fn packaged_crate_inside_repository() {
+		let dir = tempfile::tempdir().unwrap();
+		let repository = git2::Repository::init(dir.path()).unwrap();
+		let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+		let tree = repository
+			.find_tree(repository.index().unwrap().write_tree().unwrap())
+			.unwrap();
+		repository
+			.commit(Some("HEAD"), &signature, &signature, "first", &tree, &[])
+			.unwrap();
+
+		let manifest_dir = dir.path().join("target/package/sample-0.1.0");
+		std::fs::create_dir_all(&manifest_dir).unwrap();
+		std::fs::write(
+			manifest_dir.join(".cargo_vcs_info.json"),
+			r#"{"git": {"sha1": "2b4a5dc0e2a1f9e3c1d7b1e33bd0e7d36a4c2b8f"}, "path_in_vcs": "sample"}"#,
+		)
+		.unwrap();
+		let krate = CrateContext {
+			manifest_dir,
+			git: backend::GitContext {
+				name: "sample".to_string(),
+				version: "0.1.0".to_string(),
+				git_dir: None,
+				git_work_tree: None,
+			},
+		};
+
+		match collect(&krate, &Default::default()).unwrap() {
+			Some(VersionControl::CargoVcsInfo(info)) => {
+				assert_eq!(info.commit_id, "2b4a5dc0e2a1f9e3c1d7b1e33bd0e7d36a4c2b8f");
+				assert_eq!(info.path_in_vcs, "sample");
+			}
+			info => panic!("Expected the packaged commit, got {:?}", info),
+		}
+
+		// Without `.cargo_vcs_info.json`, the enclosing repository is reported
+		std::fs::remove_file(krate.manifest_dir.join(".cargo_vcs_info.json")).unwrap();
+		assert!(matches!(
+			collect(&krate, &Default::default()).unwrap(),
+			Some(VersionControl::Git(_))
+		));
+	}
 }
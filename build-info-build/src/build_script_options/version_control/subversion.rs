@@ -0,0 +1,213 @@
+use std::{
+	io::ErrorKind,
+	path::{Path, PathBuf},
+	process::Command,
+};
+
+use anyhow::{anyhow, Context, Result};
+use build_info_common::SubversionInfo;
+
+use super::{super::rerun_if_changed, CrateContext};
+
+/// Returns `None` if the crate is not part of a Subversion working copy, or if `svn` is not installed or cannot read it.
+pub(crate) fn get_info(krate: &CrateContext) -> Result<Option<SubversionInfo>> {
+	let dir = &krate.manifest_dir;
+	let root = match find_root(dir) {
+		Some(root) => root,
+		None => return Ok(None),
+	};
+
+	// The working copy database records the revision of every file, and is updated by `svn update` and `svn commit`
+	let database = root.join(".svn").join("wc.db");
+	if database.exists() {
+		rerun_if_changed(&database)?;
+	}
+
+	let info = match svn(dir, &["info", "--xml", "."])? {
+		Some(info) => info,
+		None => return Ok(None),
+	};
+	let mut info = parse_info(&info)?;
+	info.modified = match svn(dir, &["status", "--quiet", "--ignore-externals", "."])? {
+		Some(status) => !status.trim().is_empty(),
+		None => return Ok(None),
+	};
+	Ok(Some(info))
+}
+
+/// Finds the innermost directory that contains a `.svn` directory, which is the root of the working copy
+fn find_root(dir: &Path) -> Option<PathBuf> {
+	dir
+		.ancestors()
+		.find(|dir| dir.join(".svn").is_dir())
+		.map(Path::to_path_buf)
+}
+
+/// Returns `None` if `svn` is not installed, or if it fails (e.g., because the working copy was created by a newer
+/// client, or is locked)
+fn svn(dir: &Path, args: &[&str]) -> Result<Option<String>> {
+	let output = match Command::new("svn")
+		.arg("--non-interactive")
+		.args(args)
+		.current_dir(dir)
+		.output()
+	{
+		Ok(output) => output,
+		Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+		Err(err) => return Err(err).context("Could not run `svn`"),
+	};
+	if !output.status.success() {
+		println!(
+			"cargo:warning=Could not collect Subversion information, as `svn {}` failed: {}",
+			args.join(" "),
+			String::from_utf8_lossy(&output.stderr).trim().replace('\n', " ")
+		);
+		return Ok(None);
+	}
+	Ok(Some(String::from_utf8(output.stdout)?))
+}
+
+/// Parses the output of `svn info --xml`; the `modified` flag is not part of it
+fn parse_info(info: &str) -> Result<SubversionInfo> {
+	let (entry, entry_content) = element(info, "entry").ok_or_else(|| anyhow!("`svn info` did not return an `entry`"))?;
+	let (commit, commit_content) =
+		element(entry_content, "commit").ok_or_else(|| anyhow!("`svn info` did not return the last `commit`"))?;
+	let revision = |start_tag: &str, name: &str| -> Result<u32> {
+		let revision = attribute(start_tag, "revision")
+			.ok_or_else(|| anyhow!("`svn info` did not return the revision of the `{}`", name))?;
+		Ok(revision.parse()?)
+	};
+
+	Ok(SubversionInfo {
+		url: text(entry_content, "url").ok_or_else(|| anyhow!("`svn info` did not return the URL"))?,
+		revision: revision(entry, "entry")?,
+		last_changed_revision: revision(commit, "commit")?,
+		last_changed_author: text(commit_content, "author"),
+		modified: false,
+	})
+}
+
+/// The contents of the start tag (without the angle brackets) and the contents of the first element `<name ...>...</name>`;
+/// the contents are empty for an empty-element tag `<name .../>`
+fn element<'a>(xml: &'a str, name: &str) -> Option<(&'a str, &'a str)> {
+	let pattern = format!("<{}", name);
+	let mut rest = xml;
+	while let Some(start) = rest.find(&pattern) {
+		let tag = &rest[start + 1..];
+		let end = tag.find('>')?;
+		if tag[name.len()..].starts_with(|c: char| c.is_whitespace() || c == '>' || c == '/') {
+			let start_tag = &tag[..end];
+			if start_tag.ends_with('/') {
+				return Some((start_tag, ""));
+			}
+			let content = &tag[end + 1..];
+			let content_end = content.find(&format!("</{}>", name))?;
+			return Some((start_tag, &content[..content_end]));
+		}
+		rest = &tag[end..];
+	}
+	None
+}
+
+fn attribute<'a>(start_tag: &'a str, name: &str) -> Option<&'a str> {
+	let pattern = format!("{}=\"", name);
+	let mut rest = start_tag;
+	while let Some(start) = rest.find(&pattern) {
+		let value = &rest[start + pattern.len()..];
+		// Only match whole attribute names, e.g., not `revision` in `wc-revision`
+		if rest[..start].ends_with(char::is_whitespace) {
+			return value.find('"').map(|end| &value[..end]);
+		}
+		rest = value;
+	}
+	None
+}
+
+/// The text of the first element `<name>...</name>`
+fn text(xml: &str, name: &str) -> Option<String> {
+	let start = xml.find(&format!("<{}>", name))? + name.len() + 2;
+	let end = xml[start..].find(&format!("</{}>", name))? + start;
+	Some(unescape(&xml[start..end]))
+}
+
+fn unescape(text: &str) -> String {
+	text
+		.replace("&lt;", "<")
+		.replace("&gt;", ">")
+		.replace("&quot;", "\"")
+		.replace("&apos;", "'")
+		.replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod test {
+	use pretty_assertions::assert_eq;
+
+	use super::*;
+
+	#[test]
+	fn parse_working_copy() {
+		let info = r#"<?xml version="1.0" encoding="UTF-8"?>
+<info>
+<entry
+   kind="dir"
+   path="."
+   revision="1234">
+<url>https://svn.example.com/repo/trunk/tools%20&amp;%20scripts</url>
+<relative-url>^/trunk/tools%20&amp;%20scripts</relative-url>
+<repository>
+<root>https://svn.example.com/repo</root>
+<uuid>13f79535-47bb-0310-9956-ffa450edef68</uuid>
+</repository>
+<wc-info>
+<wcroot-abspath>/home/user/tools</wcroot-abspath>
+<schedule>normal</schedule>
+<depth>infinity</depth>
+</wc-info>
+<commit
+   revision="1200">
+<author>alice</author>
+<date>2020-06-02T15:50:00.000000Z</date>
+</commit>
+</entry>
+</info>
+"#;
+		assert_eq!(
+			parse_info(info).unwrap(),
+			SubversionInfo {
+				url: "https://svn.example.com/repo/trunk/tools%20&%20scripts".to_string(),
+				revision: 1234,
+				last_changed_revision: 1200,
+				last_changed_author: Some("alice".to_string()),
+				modified: false,
+			}
+		);
+	}
+
+	#[test]
+	fn commit_without_author() {
+		let info = r#"<info><entry kind="dir" path="." revision="0"><url>file:///srv/svn/repo</url><commit revision="0"><date>2020-06-02T15:50:00.000000Z</date></commit></entry></info>"#;
+		let info = parse_info(info).unwrap();
+		assert_eq!(info.revision, 0);
+		assert_eq!(info.last_changed_author, None);
+	}
+
+	#[test]
+	fn elements_are_scoped() {
+		// Elements outside of the entry or its last commit must not be picked up
+		let info = r#"<info><author>mallory</author><url>https://evil.example.com</url><entry revision="7"><commit revision="5"></commit><url>https://svn.example.com/repo</url></entry></info>"#;
+		let info = parse_info(info).unwrap();
+		assert_eq!(info.url, "https://svn.example.com/repo");
+		assert_eq!(info.revision, 7);
+		assert_eq!(info.last_changed_revision, 5);
+		assert_eq!(info.last_changed_author, None);
+	}
+
+	#[test]
+	fn attributes() {
+		let (tag, content) = element(r#"<entries><entry wc-revision="2" revision="1"/></entries>"#, "entry").unwrap();
+		assert_eq!(content, "");
+		assert_eq!(attribute(tag, "revision"), Some("1"));
+		assert_eq!(attribute(tag, "kind"), None);
+	}
+}
@@ -0,0 +1,99 @@
+use std::path::PathBuf;
+
+/// Errors that can occur while collecting build information
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum BuildInfoError {
+	/// `build_info_build::build_script` was called more than once
+	AlreadyRun,
+
+	/// An environment variable that should have been set by cargo is missing
+	MissingEnvVar { name: String },
+
+	/// An environment variable has a value that cannot be interpreted
+	InvalidEnvVar { name: String, value: String },
+
+	/// A name passed to `BuildScriptOptions::capture_env` cannot name an environment variable
+	InvalidEnvVarName { name: String },
+
+	/// A path cannot be passed to cargo, as it is not valid UTF-8
+	NonUtf8Path { path: PathBuf },
+
+	/// A glob pattern passed to the `BuildScriptOptions` is invalid
+	InvalidPattern { pattern: String, reason: String },
+
+	/// The pattern passed to `BuildScriptOptions::crate_tag_pattern` does not contain `{version}` exactly once
+	InvalidCrateTagPattern { pattern: String },
+
+	/// The manifest (`Cargo.toml`) could not be read
+	Manifest(anyhow::Error),
+
+	/// Information about the compiler could not be obtained
+	Compiler(anyhow::Error),
+
+	/// The dependency graph could not be resolved via `cargo metadata`
+	Dependencies(anyhow::Error),
+
+	/// Information about the version control system could not be obtained
+	VersionControl(anyhow::Error),
+
+	/// The collected build information could not be serialized
+	Serialization(anyhow::Error),
+}
+
+impl BuildInfoError {
+	/// Reads an environment variable that cargo sets for build scripts
+	pub(crate) fn env_var(name: &str) -> Result<String, BuildInfoError> {
+		std::env::var(name).map_err(|err| match err {
+			std::env::VarError::NotPresent => BuildInfoError::MissingEnvVar { name: name.to_string() },
+			std::env::VarError::NotUnicode(value) => BuildInfoError::InvalidEnvVar {
+				name: name.to_string(),
+				value: value.to_string_lossy().into_owned(),
+			},
+		})
+	}
+}
+
+impl std::fmt::Display for BuildInfoError {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			BuildInfoError::AlreadyRun => write!(f, "The build script may only be run once."),
+			BuildInfoError::MissingEnvVar { name } => {
+				write!(f, "Expected environment variable `{}` to be set by cargo", name)
+			}
+			BuildInfoError::InvalidEnvVar { name, value } => {
+				write!(f, "Environment variable `{}` has an unexpected value {:?}", name, value)
+			}
+			BuildInfoError::InvalidEnvVarName { name } => write!(f, "{:?} is not a valid environment variable name", name),
+			BuildInfoError::NonUtf8Path { path } => write!(f, "Path {:?} is not valid UTF-8", path),
+			BuildInfoError::InvalidPattern { pattern, reason } => {
+				write!(f, "Invalid glob pattern {:?}: {}", pattern, reason)
+			}
+			BuildInfoError::InvalidCrateTagPattern { pattern } => write!(
+				f,
+				"The crate tag pattern {:?} must contain `{{version}}` exactly once",
+				pattern
+			),
+			BuildInfoError::Manifest(err) => write!(f, "Could not read the manifest: {:#}", err),
+			BuildInfoError::Compiler(err) => write!(f, "Could not determine the compiler version: {:#}", err),
+			BuildInfoError::Dependencies(err) => write!(f, "Could not resolve the dependencies: {:#}", err),
+			BuildInfoError::VersionControl(err) => {
+				write!(f, "Could not collect version control information: {:#}", err)
+			}
+			BuildInfoError::Serialization(err) => write!(f, "Could not serialize the build information: {:#}", err),
+		}
+	}
+}
+
+impl std::error::Error for BuildInfoError {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			BuildInfoError::Manifest(err)
+			| BuildInfoError::Compiler(err)
+			| BuildInfoError::Dependencies(err)
+			| BuildInfoError::VersionControl(err)
+			| BuildInfoError::Serialization(err) => Some(err.as_ref()),
+			_ => None,
+		}
+	}
+}
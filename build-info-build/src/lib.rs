@@ -7,6 +7,20 @@ This crate is used to collect build info for consumption by the `build-info` cra
 build_info_build::build_script();
 ```
 
+If you would rather handle failures yourself, use `try_build`. The handling of failures of individual collectors can be
+configured via `on_failure`:
+
+```rust,no_run
+use build_info_build::{Collector, FailurePolicy};
+
+let build_info = build_info_build::build_script()
+	.on_failure(Collector::VersionControl, FailurePolicy::Warn)
+	.try_build();
+if let Err(err) = build_info {
+	panic!("Could not collect build information: {}", err);
+}
+```
+
 # Features
 The ´build-info-build` crate has the following features:
 
@@ -17,14 +31,18 @@ The ´build-info-build` crate has the following features:
 #![allow(clippy::tabs_in_doc_comments)]
 
 pub use build_info_common::{
-	semver, BuildInfo, CompilerChannel, CompilerInfo, CrateInfo, GitInfo, OptimizationLevel, VersionControl,
+	semver, BuildInfo, CargoVcsInfo, CompilerChannel, CompilerInfo, CrateInfo, CustomValue, Endianness, GitInfo,
+	MercurialInfo, OptimizationLevel, SubmoduleInfo, SubversionInfo, TargetInfo, VersionControl,
 };
 // By reusing the `chrono` crate from `build-info-build` instead of from `build-info-common`, we do not rely on the
 // crates merged into one. This crate will fail to compile if the versions have an incompatible API.
 pub use chrono;
 
 mod build_script_options;
-pub use build_script_options::BuildScriptOptions;
+pub use build_script_options::{BuildScriptOptions, Collector, FailurePolicy};
+
+mod error;
+pub use error::BuildInfoError;
 
 /// Call this function in your `build.rs` script to generate the data consumed by the `build_info` crate.
 /// Additional customization options are available by manipulating the return type.
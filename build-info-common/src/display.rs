@@ -23,6 +23,45 @@ impl std::fmt::Display for crate::OptimizationLevel {
 	}
 }
 
+impl std::fmt::Display for crate::ProfileSettings {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		write!(
+			f,
+			"debug-assertions={}, overflow-checks={}, panic={}, lto={}, codegen-units={}, debuginfo={}",
+			self.debug_assertions, self.overflow_checks, self.panic, self.lto, self.codegen_units, self.debuginfo
+		)
+	}
+}
+
+impl std::fmt::Display for crate::PanicStrategy {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Unwind => write!(f, "unwind"),
+			Self::Abort => write!(f, "abort"),
+		}
+	}
+}
+
+impl std::fmt::Display for crate::CustomValue {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			Self::Bool(value) => write!(f, "{}", value),
+			Self::Integer(value) => write!(f, "{}", value),
+			Self::String(value) => write!(f, "{}", value),
+			Self::Map(map) => {
+				write!(f, "{{")?;
+				for (i, (key, value)) in map.iter().enumerate() {
+					if i > 0 {
+						write!(f, ", ")?;
+					}
+					write!(f, "{}: {}", key, value)?;
+				}
+				write!(f, "}}")
+			}
+		}
+	}
+}
+
 impl std::fmt::Display for crate::CrateInfo {
 	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
 		write!(f, "{} v{}", self.name, self.version)
@@ -46,10 +85,25 @@ impl std::fmt::Display for crate::CompilerInfo {
 	}
 }
 
+impl std::fmt::Display for crate::TargetInfo {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		write!(f, "{}-{}-{}", self.arch, self.vendor, self.os)?;
+
+		if !self.env.is_empty() {
+			write!(f, "-{}", self.env)?;
+		}
+
+		Ok(())
+	}
+}
+
 impl std::fmt::Display for crate::VersionControl {
 	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
 		match self {
 			crate::VersionControl::Git(ref git) => write!(f, "{}", git),
+			crate::VersionControl::Mercurial(ref mercurial) => write!(f, "{}", mercurial),
+			crate::VersionControl::Subversion(ref subversion) => write!(f, "{}", subversion),
+			crate::VersionControl::CargoVcsInfo(ref cargo_vcs_info) => write!(f, "{}", cargo_vcs_info),
 		}
 	}
 }
@@ -69,3 +123,61 @@ impl std::fmt::Display for crate::GitInfo {
 		Ok(())
 	}
 }
+
+impl std::fmt::Display for crate::MercurialInfo {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		write!(f, "{}", &self.changeset_id)?;
+
+		if self.dirty {
+			write!(f, ".+")?;
+		}
+
+		write!(f, " ({})", self.branch)
+	}
+}
+
+impl std::fmt::Display for crate::SubversionInfo {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		write!(f, "r{}", self.revision)?;
+
+		if self.modified {
+			write!(f, ".+")?;
+		}
+
+		write!(f, " ({})", self.url)
+	}
+}
+
+impl std::fmt::Display for crate::CargoVcsInfo {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		write!(f, "{}", &self.commit_id)?;
+
+		if self.dirty {
+			write!(f, ".+")?;
+		}
+
+		if !self.path_in_vcs.is_empty() {
+			write!(f, " ({})", self.path_in_vcs)?;
+		}
+
+		Ok(())
+	}
+}
+
+/// Follows the format of `git submodule status`: the commit id is prefixed with `-` if the submodule is not
+/// initialized, and with `+` if it does not match the commit recorded in the superproject.
+impl std::fmt::Display for crate::SubmoduleInfo {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match &self.commit_id {
+			None => write!(f, "-{}", self.recorded_commit_id.as_deref().unwrap_or_default())?,
+			Some(commit_id) if !self.matches_superproject => write!(f, "+{}", commit_id)?,
+			Some(commit_id) => write!(f, "{}", commit_id)?,
+		}
+
+		if self.dirty {
+			write!(f, ".+")?;
+		}
+
+		write!(f, " {}", self.path)
+	}
+}
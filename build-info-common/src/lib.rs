@@ -8,8 +8,10 @@ For example, `build_info_common::BuildInfo` should be used as `build_info::Build
 
 #![forbid(unsafe_code)]
 
+use std::collections::BTreeMap;
+
 pub use chrono;
-use chrono::{DateTime, NaiveDate, Utc};
+use chrono::{DateTime, FixedOffset, NaiveDate, Utc};
 use derive_more::Display;
 pub use semver;
 use semver::Version;
@@ -42,20 +44,121 @@ pub struct BuildInfo {
 	/// Updated whenever `build.rs` is rerun.
 	pub timestamp: DateTime<Utc>,
 
-	/// Cargo currently supports two different build types: `"Release"` and `"Debug"`
+	/// The name of the profile, as used for the output directory (e.g., `"debug"`, `"release"` or the name of a custom
+	/// profile such as `"release-lto"`)
 	pub profile: String,
 
 	/// The optimization level can be set in `Cargo.toml` for each profile
 	pub optimization_level: OptimizationLevel,
 
+	/// The effective settings of the profile
+	pub profile_settings: ProfileSettings,
+
 	/// Information about the current crate
 	pub crate_info: CrateInfo,
 
-	/// Information about the compiler used.
-	pub compiler: CompilerInfo,
+	/// Information about the compiler used; `None` if it could not be collected and the failure policy of the build script
+	/// allowed to continue without it.
+	pub compiler: Option<CompilerInfo>,
 
-	/// `Some` if the project is inside a check-out of a supported version control system.
+	/// `Some` if the project is inside a check-out of a supported version control system, or if it was packaged from a
+	/// git repository by `cargo package`.
 	pub version_control: Option<VersionControl>,
+
+	/// User-defined data, as set by `BuildScriptOptions::custom` in `build.rs`.
+	pub custom: BTreeMap<String, CustomValue>,
+
+	/// Environment variables captured via `BuildScriptOptions::capture_env` in `build.rs`.
+	/// Variables that were not set at build time are recorded as `None`.
+	pub environment: BTreeMap<String, Option<String>>,
+}
+
+/// A user-defined value that can be attached to the `BuildInfo` from `build.rs`
+#[cfg_attr(feature = "enable-serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum CustomValue {
+	Bool(bool),
+	Integer(i64),
+	String(String),
+	Map(BTreeMap<String, CustomValue>),
+}
+
+impl CustomValue {
+	pub fn as_bool(&self) -> Option<bool> {
+		match self {
+			CustomValue::Bool(value) => Some(*value),
+			_ => None,
+		}
+	}
+
+	pub fn as_i64(&self) -> Option<i64> {
+		match self {
+			CustomValue::Integer(value) => Some(*value),
+			_ => None,
+		}
+	}
+
+	pub fn as_str(&self) -> Option<&str> {
+		match self {
+			CustomValue::String(value) => Some(value),
+			_ => None,
+		}
+	}
+
+	pub fn as_map(&self) -> Option<&BTreeMap<String, CustomValue>> {
+		match self {
+			CustomValue::Map(value) => Some(value),
+			_ => None,
+		}
+	}
+}
+
+impl From<bool> for CustomValue {
+	fn from(value: bool) -> Self {
+		CustomValue::Bool(value)
+	}
+}
+
+macro_rules! impl_custom_value_from_integer {
+	($($t:ty),*) => {
+		$(
+			impl From<$t> for CustomValue {
+				fn from(value: $t) -> Self {
+					CustomValue::Integer(value.into())
+				}
+			}
+		)*
+	};
+}
+impl_custom_value_from_integer!(i8, i16, i32, i64, u8, u16, u32);
+
+impl From<&str> for CustomValue {
+	fn from(value: &str) -> Self {
+		CustomValue::String(value.to_string())
+	}
+}
+
+impl From<String> for CustomValue {
+	fn from(value: String) -> Self {
+		CustomValue::String(value)
+	}
+}
+
+impl<K: Into<String>, V: Into<CustomValue>> FromIterator<(K, V)> for CustomValue {
+	fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+		CustomValue::Map(
+			iter
+				.into_iter()
+				.map(|(key, value)| (key.into(), value.into()))
+				.collect(),
+		)
+	}
+}
+
+impl<V: Into<CustomValue>> From<BTreeMap<String, V>> for CustomValue {
+	fn from(value: BTreeMap<String, V>) -> Self {
+		value.into_iter().collect()
+	}
 }
 
 /// The various possible optimization levels
@@ -71,6 +174,39 @@ pub enum OptimizationLevel {
 	Oz,
 }
 
+/// Effective settings of the profile that was used to build the crate
+#[cfg_attr(feature = "enable-pyo3", pyo3::pyclass)]
+#[cfg_attr(feature = "enable-serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct ProfileSettings {
+	/// `true` iff `debug_assertions` are enabled
+	pub debug_assertions: bool,
+
+	/// `true` iff integer overflow checks are enabled
+	pub overflow_checks: bool,
+
+	/// The panic strategy
+	pub panic: PanicStrategy,
+
+	/// The link-time optimization setting: `"false"` (thin local LTO), `"thin"`, `"fat"` or `"off"`
+	pub lto: String,
+
+	/// The number of codegen units
+	pub codegen_units: u32,
+
+	/// The amount of debug information: `"none"`, `"line-directives-only"`, `"line-tables-only"`, `"limited"` or `"full"`
+	pub debuginfo: String,
+}
+
+/// The strategy used when a panic occurs
+#[cfg_attr(feature = "enable-pyo3", pyo3::pyclass)]
+#[cfg_attr(feature = "enable-serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum PanicStrategy {
+	Unwind,
+	Abort,
+}
+
 /// Information about the current crate (i.e., the crate for which build information has been generated)
 #[cfg_attr(feature = "enable-pyo3", pyo3::pyclass)]
 #[cfg_attr(feature = "enable-serde", derive(Serialize, Deserialize))]
@@ -82,6 +218,12 @@ pub struct CrateInfo {
 	/// The version, as defined in `Cargo.toml`.
 	pub version: Version,
 
+	/// The version derived from the nearest git tag (e.g., `1.4.3-dev.17+gabc1234` for a build 17 commits after the tag
+	/// `v1.4.2`).
+	/// Will only be filled with data if enabled in `build.rs` via
+	/// `build_info_build::build_script().version_from_git_tags(true)`.
+	pub git_version: Option<Version>,
+
 	/// The authors, as defined in `Cargo.toml`.
 	pub authors: Vec<String>,
 
@@ -95,8 +237,17 @@ pub struct CrateInfo {
 	pub available_features: Vec<String>,
 
 	/// Dependencies of this crate.
-	/// Will only be filled with data if `build-info-build` has the `dependencies` feature enabled.
+	/// Will only be filled with data if dependency collection is enabled in `build.rs` via
+	/// `build_info_build::build_script().collect_dependencies(true)`.
 	pub dependencies: Vec<CrateInfo>,
+
+	/// The root directory of the workspace that contains this crate.
+	/// Only set for the crate that is being built, not for its dependencies.
+	pub workspace_root: Option<String>,
+
+	/// The directory of this crate relative to `workspace_root`, using `/` as separator (`"."` for the root crate).
+	/// Only set for the crate that is being built, not for its dependencies.
+	pub path_in_workspace: Option<String>,
 }
 
 /// `rustc` version and configuration
@@ -121,6 +272,87 @@ pub struct CompilerInfo {
 
 	/// Identifies the target architecture for which the crate is being compiled
 	pub target_triple: String,
+
+	/// Details about the target platform, as reported by cargo via the `CARGO_CFG_TARGET_*` environment variables
+	pub target: TargetInfo,
+
+	/// The extra flags passed to `rustc` (e.g., via `RUSTFLAGS` or `build.rustflags`)
+	pub rustflags: Vec<String>,
+
+	/// The `rustc` wrapper that is used for all crates (e.g., `sccache`), if any
+	pub rustc_wrapper: Option<String>,
+
+	/// The `rustc` wrapper that is used for workspace members only, if any
+	pub rustc_workspace_wrapper: Option<String>,
+
+	/// The linker that was configured for the target, if any
+	pub linker: Option<String>,
+
+	/// The CPU that code is generated for, as set by `-C target-cpu`, if any
+	pub target_cpu: Option<String>,
+
+	/// The sanitizers that are enabled via `-Z sanitizer` (e.g., `["address"]`)
+	pub sanitizers: Vec<String>,
+
+	/// `true` iff coverage instrumentation is enabled via `-C instrument-coverage`
+	pub instrument_coverage: bool,
+
+	/// `true` iff an instrumented binary for profile-guided optimization is built via `-C profile-generate`
+	pub profile_generate: bool,
+
+	/// `true` iff profile-guided optimization data is used via `-C profile-use`
+	pub profile_use: bool,
+
+	/// Version of the LLVM backend used by `rustc` (e.g., `"14.0"`), if known
+	pub llvm_version: Option<String>,
+
+	/// Version of `cargo`, if it could be determined
+	pub cargo_version: Option<Version>,
+
+	/// Name of the active `rustup` toolchain (e.g., `"stable-x86_64-unknown-linux-gnu"`), if built via `rustup`
+	pub rustup_toolchain: Option<String>,
+
+	/// The toolchain pinned in `rust-toolchain.toml` or `rust-toolchain`, if any
+	pub pinned_toolchain: Option<String>,
+}
+
+/// Details about the platform for which the crate is being compiled
+#[cfg_attr(feature = "enable-pyo3", pyo3::pyclass)]
+#[cfg_attr(feature = "enable-serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct TargetInfo {
+	/// The target architecture (e.g., `"x86_64"` or `"aarch64"`)
+	pub arch: String,
+
+	/// The target operating system (e.g., `"linux"`, `"windows"` or `"none"`)
+	pub os: String,
+
+	/// The target family (e.g., `["unix"]` or `["unix", "wasm"]`); empty for bare-metal targets
+	pub family: Vec<String>,
+
+	/// The target environment (e.g., `"gnu"` or `"musl"`); may be empty
+	pub env: String,
+
+	/// The target vendor (e.g., `"unknown"`, `"pc"` or `"apple"`)
+	pub vendor: String,
+
+	/// The endianness of the target
+	pub endian: Endianness,
+
+	/// The width of a pointer on the target in bits
+	pub pointer_width: u8,
+
+	/// The target features that are enabled (e.g., `["fxsr", "sse", "sse2"]`)
+	pub features: Vec<String>,
+}
+
+/// The byte order of a target
+#[cfg_attr(feature = "enable-pyo3", pyo3::pyclass)]
+#[cfg_attr(feature = "enable-serde", derive(Serialize, Deserialize))]
+#[derive(Display, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum Endianness {
+	Big,
+	Little,
 }
 
 /// `rustc` distribution channel (some compiler features are only available on specific channels)
@@ -137,15 +369,40 @@ pub enum CompilerChannel {
 /// Support for different version control systems
 #[cfg_attr(feature = "enable-serde", derive(Serialize, Deserialize))]
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[allow(clippy::large_enum_variant)] // There is only ever a single `VersionControl` object
 pub enum VersionControl {
 	Git(GitInfo),
+	Mercurial(MercurialInfo),
+	Subversion(SubversionInfo),
+	CargoVcsInfo(CargoVcsInfo),
 }
 
 impl VersionControl {
 	pub fn git(&self) -> Option<&GitInfo> {
 		match self {
 			VersionControl::Git(git) => Some(git),
-			// _ => None, // Pattern currently unreachable
+			_ => None,
+		}
+	}
+
+	pub fn mercurial(&self) -> Option<&MercurialInfo> {
+		match self {
+			VersionControl::Mercurial(mercurial) => Some(mercurial),
+			_ => None,
+		}
+	}
+
+	pub fn subversion(&self) -> Option<&SubversionInfo> {
+		match self {
+			VersionControl::Subversion(subversion) => Some(subversion),
+			_ => None,
+		}
+	}
+
+	pub fn cargo_vcs_info(&self) -> Option<&CargoVcsInfo> {
+		match self {
+			VersionControl::CargoVcsInfo(cargo_vcs_info) => Some(cargo_vcs_info),
+			_ => None,
 		}
 	}
 }
@@ -180,4 +437,220 @@ pub struct GitInfo {
 
 	/// All tags that point to the current commit (e.g., `["v0.0.10", "sample@v0.0.10"]`)
 	pub tags: Vec<String>,
+
+	/// The closest tag that is reachable from the current commit, if any (e.g., `"v1.4.2"`)
+	///
+	/// Both annotated and lightweight tags are considered.
+	pub nearest_tag: Option<String>,
+
+	/// The number of commits since `nearest_tag`, if any (`0` if the tag points to the current commit)
+	pub commits_since_tag: Option<u32>,
+
+	/// The closest tag that is reachable from the current commit and names a semantic version, like `"v1.4.2"` or
+	/// `"1.4.2"`, if any
+	///
+	/// Tags that are not versions (e.g., `"nightly"`) are skipped, so this may be an older tag than `nearest_tag`.
+	pub nearest_version_tag: Option<String>,
+
+	/// The number of commits since `nearest_version_tag`, if any
+	pub commits_since_version_tag: Option<u32>,
+
+	/// Describes the current commit like `git describe --tags --always --dirty` (e.g., `"v1.4.2-17-gabc1234-dirty"`)
+	///
+	/// If no tag is reachable from the current commit, this is the short commit hash, followed by `-dirty` as appropriate.
+	pub describe: String,
+
+	/// The tags of the current crate that point to the current commit (e.g., `["sample@v0.0.10"]`)
+	///
+	/// Tags of the current crate match the pattern set via `build_info_build::build_script().crate_tag_pattern(...)`,
+	/// which defaults to `"{crate}@v{version}"`.
+	pub crate_tags: Vec<String>,
+
+	/// The closest tag of the current crate that is reachable from the current commit, if any
+	pub nearest_crate_tag: Option<String>,
+
+	/// The number of commits since `nearest_crate_tag`, if any
+	pub commits_since_crate_tag: Option<u32>,
+
+	/// The version parsed from `nearest_crate_tag`
+	pub crate_tag_version: Option<Version>,
+
+	/// Whether `crate_tag_version` agrees with the version in `Cargo.toml` (`None` if there is no crate tag)
+	pub crate_tag_version_matches: Option<bool>,
+
+	/// Full commit hash of the last commit that changed the directory of the current crate
+	///
+	/// Unlike `commit_id`, this does not change when unrelated parts of the repository change. It is `None` if the crate
+	/// has not been committed yet, or if it is not part of the worktree.
+	pub crate_commit_id: Option<String>,
+
+	/// Timestamp of the commit identified by `crate_commit_id`
+	pub crate_commit_timestamp: Option<DateTime<Utc>>,
+
+	/// `true` iff the directory of the current crate had uncommitted changes when building the project.
+	pub crate_dirty: bool,
+
+	/// The URLs of all configured remotes, by name (e.g., `{"origin": "https://github.com/user/repo.git"}`)
+	///
+	/// Credentials and query strings are removed from URLs (only `ssh` and `git` URLs keep their user name), and the
+	/// scheme and host are lowercased.
+	pub remotes: BTreeMap<String, String>,
+
+	/// The name of the remote that the upstream of the current branch belongs to, if any (e.g., `"origin"`)
+	pub upstream_remote: Option<String>,
+
+	/// The upstream of the current branch, if any (e.g., `"origin/main"`)
+	pub upstream_branch: Option<String>,
+
+	/// The number of commits on the current branch that are not on its upstream, i.e., that have not been pushed
+	///
+	/// This is `None` if there is no upstream. Note that the upstream is only updated by `git fetch`, so this reflects
+	/// the state of the remote repository at the time of the last fetch.
+	///
+	/// Since `BuildScriptOptions::build` returns the collected `BuildInfo`, a build script can use this to refuse to
+	/// build a release from commits that have not been pushed.
+	pub commits_ahead: Option<u32>,
+
+	/// The number of commits on the upstream that are not on the current branch (see `commits_ahead`)
+	pub commits_behind: Option<u32>,
+
+	/// Name of the author of the current commit
+	/// Will only be filled with data if enabled in `build.rs` via
+	/// `build_info_build::build_script().collect_commit_identities(true)`.
+	pub author_name: Option<String>,
+
+	/// Email address of the author of the current commit (see `author_name`)
+	pub author_email: Option<String>,
+
+	/// Name of the committer of the current commit (see `author_name`)
+	pub committer_name: Option<String>,
+
+	/// Email address of the committer of the current commit (see `author_name`)
+	pub committer_email: Option<String>,
+
+	/// Time at which the current commit was authored, with the UTC offset of the author
+	/// Will only be filled with data if enabled in `build.rs` via
+	/// `build_info_build::build_script().collect_commit_timestamps(true)`.
+	pub author_timestamp: Option<DateTime<FixedOffset>>,
+
+	/// Time at which the current commit was committed, with the UTC offset of the committer (see `author_timestamp`)
+	pub committer_timestamp: Option<DateTime<FixedOffset>>,
+
+	/// The first line of the message of the current commit
+	/// Will only be filled with data if enabled in `build.rs` via
+	/// `build_info_build::build_script().collect_commit_summary(true)`.
+	pub summary: Option<String>,
+
+	/// The trailers of the message of the current commit, such as `Reviewed-by: ...` (e.g., `{"Reviewed-by": ["..."]}`)
+	/// Will only be filled with data if enabled in `build.rs` via
+	/// `build_info_build::build_script().collect_commit_trailers(true)`.
+	pub trailers: BTreeMap<String, Vec<String>>,
+
+	/// The submodules of the repository, sorted by path
+	pub submodules: Vec<SubmoduleInfo>,
+}
+
+/// Information about a git submodule
+#[cfg_attr(feature = "enable-pyo3", pyo3::pyclass)]
+#[cfg_attr(feature = "enable-serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct SubmoduleInfo {
+	/// Path of the submodule, relative to the root of the superproject (e.g., `"vendor/zlib"`)
+	pub path: String,
+
+	/// Full hash of the commit that is checked out in the submodule; `None` if the submodule is not initialized
+	pub commit_id: Option<String>,
+
+	/// Full hash of the commit that the current commit of the superproject records for the submodule
+	pub recorded_commit_id: Option<String>,
+
+	/// `true` iff the checked out commit is the one recorded in the superproject
+	pub matches_superproject: bool,
+
+	/// `true` iff the submodule contains uncommitted changes or untracked files
+	pub dirty: bool,
+}
+
+/**
+Information about a Mercurial repository
+
+If a Mercurial repository is detected (and, thereby, this information included), the build script will be rerun whenever
+the parent of the working directory changes.
+*/
+#[cfg_attr(feature = "enable-pyo3", pyo3::pyclass)]
+#[cfg_attr(feature = "enable-serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct MercurialInfo {
+	/// Full changeset id of the parent of the working directory
+	pub changeset_id: String,
+
+	/// Revision number of the parent of the working directory
+	///
+	/// Revision numbers are local to a repository, so the same changeset may have different revision numbers in
+	/// different clones.
+	pub revision: u32,
+
+	/// Name of the branch of the parent of the working directory (e.g., `"default"`)
+	pub branch: String,
+
+	/// Bookmarks that point to the parent of the working directory
+	pub bookmarks: Vec<String>,
+
+	/// Tags that point to the parent of the working directory, not including the implicit `tip` tag
+	pub tags: Vec<String>,
+
+	/// `true` iff tracked files in the working directory were modified, added, removed or deleted; untracked files are
+	/// not taken into account
+	pub dirty: bool,
+}
+
+/**
+Information about a Subversion working copy
+
+If a Subversion working copy is detected (and, thereby, this information included), the build script will be rerun
+whenever the working copy is updated.
+*/
+#[cfg_attr(feature = "enable-pyo3", pyo3::pyclass)]
+#[cfg_attr(feature = "enable-serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct SubversionInfo {
+	/// URL of the crate directory in the repository (e.g., `"https://svn.example.com/repo/trunk/tools"`)
+	pub url: String,
+
+	/// Revision to which the crate directory was updated
+	pub revision: u32,
+
+	/// Last revision (at or before `revision`) in which the crate directory was changed
+	pub last_changed_revision: u32,
+
+	/// Author of the last change (see `last_changed_revision`)
+	pub last_changed_author: Option<String>,
+
+	/// `true` iff the working copy contains local modifications; unversioned files are not considered
+	pub modified: bool,
+}
+
+/**
+Information about the commit from which a crate was packaged
+
+When `cargo package` (or `cargo publish`) creates a crate archive, it records the commit in `.cargo_vcs_info.json`. This
+information is used whenever the crate is built from such an archive (e.g., after downloading it from crates.io), even if
+the archive was unpacked inside of an unrelated repository.
+*/
+#[cfg_attr(feature = "enable-pyo3", pyo3::pyclass)]
+#[cfg_attr(feature = "enable-serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct CargoVcsInfo {
+	/// Identifies the packaged git commit
+	pub commit_id: String,
+
+	/// The first seven hexadecimal digits of `commit_id`
+	pub commit_short_id: String,
+
+	/// `true` iff the crate was packaged from a worktree with uncommitted changes (`cargo package --allow-dirty`)
+	pub dirty: bool,
+
+	/// The directory of the crate, relative to the root of the repository (e.g., `"build-info-build"`); empty if the
+	/// crate is located at the root
+	pub path_in_vcs: String,
 }
@@ -1,6 +1,10 @@
+use chrono::{DateTime, Offset, TimeZone};
 use pyo3::prelude::*;
 
-use crate::{BuildInfo, CompilerChannel, CompilerInfo, CrateInfo, GitInfo, OptimizationLevel, VersionControl};
+use crate::{
+	BuildInfo, CargoVcsInfo, CompilerChannel, CompilerInfo, CrateInfo, CustomValue, Endianness, GitInfo, MercurialInfo,
+	OptimizationLevel, PanicStrategy, ProfileSettings, SubmoduleInfo, SubversionInfo, TargetInfo, VersionControl,
+};
 
 /// The function generated via `build_info::build_info!` returns a reference to a statically initialized object
 /// (`&'static BuildInfo`). However, `pyo3` wants to move the result, which is of course impossible for a borrowed
@@ -11,6 +15,38 @@ impl IntoPy<PyObject> for &BuildInfo {
 	}
 }
 
+/// `CustomValue` is converted to the corresponding native Python type (`bool`, `int`, `str` or `dict`).
+impl IntoPy<PyObject> for CustomValue {
+	fn into_py(self, py: Python) -> PyObject {
+		match self {
+			CustomValue::Bool(value) => value.into_py(py),
+			CustomValue::Integer(value) => value.into_py(py),
+			CustomValue::String(value) => value.into_py(py),
+			CustomValue::Map(map) => map.into_py(py),
+		}
+	}
+}
+
+/// Python's `datetime` does not account for leap seconds, so the result is *almost* the timestamp
+fn to_py_datetime<'py, Tz: TimeZone>(py: Python<'py>, timestamp: &DateTime<Tz>) -> PyResult<&'py PyAny> {
+	use chrono::{Datelike, Timelike};
+
+	let py_datetime = py.import("datetime")?;
+	let offset = py_datetime
+		.getattr("timedelta")?
+		.call1((0, timestamp.offset().fix().local_minus_utc()))?;
+	py_datetime.getattr("datetime")?.call1((
+		timestamp.year_ce().1,
+		timestamp.month(),
+		timestamp.day(),
+		timestamp.hour(),
+		timestamp.minute(),
+		timestamp.second(),
+		timestamp.timestamp_subsec_micros() % 1_000_000,
+		py_datetime.getattr("timezone")?.call1((offset,))?,
+	))
+}
+
 #[pymethods]
 impl BuildInfo {
 	fn __str__(&self) -> String {
@@ -24,19 +60,7 @@ impl BuildInfo {
 	/// Gets *almost* the timestamp, as Python's `datetime` does not account for leap seconds
 	#[getter]
 	fn timestamp<'py>(&self, py: Python<'py>) -> PyResult<&'py PyAny> {
-		use chrono::{Datelike, Timelike};
-
-		let py_datetime = py.import("datetime")?;
-		py_datetime.getattr("datetime")?.call1((
-			self.timestamp.year_ce().1,
-			self.timestamp.month(),
-			self.timestamp.day(),
-			self.timestamp.hour(),
-			self.timestamp.minute(),
-			self.timestamp.second(),
-			self.timestamp.timestamp_subsec_micros() % 1_000_000,
-			py_datetime.getattr("timezone")?.getattr("utc")?,
-		))
+		to_py_datetime(py, &self.timestamp)
 	}
 
 	#[getter]
@@ -49,13 +73,18 @@ impl BuildInfo {
 		self.optimization_level
 	}
 
+	#[getter]
+	fn profile_settings(&self) -> ProfileSettings {
+		self.profile_settings.clone()
+	}
+
 	#[getter]
 	fn crate_info(&self) -> CrateInfo {
 		self.crate_info.clone()
 	}
 
 	#[getter]
-	fn compiler(&self) -> CompilerInfo {
+	fn compiler(&self) -> Option<CompilerInfo> {
 		self.compiler.clone()
 	}
 
@@ -63,9 +92,63 @@ impl BuildInfo {
 	fn version_control(&self, py: Python<'_>) -> Py<PyAny> {
 		match self.version_control {
 			Some(VersionControl::Git(ref git)) => git.clone().into_py(py),
+			Some(VersionControl::Mercurial(ref mercurial)) => mercurial.clone().into_py(py),
+			Some(VersionControl::Subversion(ref subversion)) => subversion.clone().into_py(py),
+			Some(VersionControl::CargoVcsInfo(ref cargo_vcs_info)) => cargo_vcs_info.clone().into_py(py),
 			None => py.None(),
 		}
 	}
+
+	#[getter]
+	fn custom(&self, py: Python<'_>) -> PyObject {
+		self.custom.clone().into_py(py)
+	}
+
+	#[getter]
+	fn environment(&self, py: Python<'_>) -> PyObject {
+		self.environment.clone().into_py(py)
+	}
+}
+
+#[pymethods]
+impl ProfileSettings {
+	fn __str__(&self) -> String {
+		format!("{}", self)
+	}
+
+	fn __repr__(&self) -> String {
+		format!("{:?}", self)
+	}
+
+	#[getter]
+	fn debug_assertions(&self) -> bool {
+		self.debug_assertions
+	}
+
+	#[getter]
+	fn overflow_checks(&self) -> bool {
+		self.overflow_checks
+	}
+
+	#[getter]
+	fn panic(&self) -> PanicStrategy {
+		self.panic
+	}
+
+	#[getter]
+	fn lto(&self) -> &str {
+		&self.lto
+	}
+
+	#[getter]
+	fn codegen_units(&self) -> u32 {
+		self.codegen_units
+	}
+
+	#[getter]
+	fn debuginfo(&self) -> &str {
+		&self.debuginfo
+	}
 }
 
 #[pymethods]
@@ -88,6 +171,11 @@ impl CrateInfo {
 		Version(self.version.clone())
 	}
 
+	#[getter]
+	fn git_version(&self) -> Option<Version> {
+		self.git_version.clone().map(Version)
+	}
+
 	#[getter]
 	fn authors(&self) -> Vec<&str> {
 		self.authors.iter().map(|s| s as &str).collect()
@@ -112,6 +200,16 @@ impl CrateInfo {
 	fn dependencies(&self) -> Vec<CrateInfo> {
 		self.dependencies.clone()
 	}
+
+	#[getter]
+	fn workspace_root(&self) -> Option<&str> {
+		self.workspace_root.as_ref().map(|s| s as &str)
+	}
+
+	#[getter]
+	fn path_in_workspace(&self) -> Option<&str> {
+		self.path_in_workspace.as_ref().map(|s| s as &str)
+	}
 }
 
 #[pymethods]
@@ -162,6 +260,127 @@ impl CompilerInfo {
 	fn target_triple(&self) -> &str {
 		&self.target_triple
 	}
+
+	#[getter]
+	fn target(&self) -> TargetInfo {
+		self.target.clone()
+	}
+
+	#[getter]
+	fn rustflags(&self) -> Vec<&str> {
+		self.rustflags.iter().map(|s| s as &str).collect()
+	}
+
+	#[getter]
+	fn rustc_wrapper(&self) -> Option<&str> {
+		self.rustc_wrapper.as_ref().map(|s| s as &str)
+	}
+
+	#[getter]
+	fn rustc_workspace_wrapper(&self) -> Option<&str> {
+		self.rustc_workspace_wrapper.as_ref().map(|s| s as &str)
+	}
+
+	#[getter]
+	fn linker(&self) -> Option<&str> {
+		self.linker.as_ref().map(|s| s as &str)
+	}
+
+	#[getter]
+	fn target_cpu(&self) -> Option<&str> {
+		self.target_cpu.as_ref().map(|s| s as &str)
+	}
+
+	#[getter]
+	fn sanitizers(&self) -> Vec<&str> {
+		self.sanitizers.iter().map(|s| s as &str).collect()
+	}
+
+	#[getter]
+	fn instrument_coverage(&self) -> bool {
+		self.instrument_coverage
+	}
+
+	#[getter]
+	fn profile_generate(&self) -> bool {
+		self.profile_generate
+	}
+
+	#[getter]
+	fn profile_use(&self) -> bool {
+		self.profile_use
+	}
+
+	#[getter]
+	fn llvm_version(&self) -> Option<&str> {
+		self.llvm_version.as_ref().map(|s| s as &str)
+	}
+
+	#[getter]
+	fn cargo_version(&self) -> Option<Version> {
+		self.cargo_version.clone().map(Version)
+	}
+
+	#[getter]
+	fn rustup_toolchain(&self) -> Option<&str> {
+		self.rustup_toolchain.as_ref().map(|s| s as &str)
+	}
+
+	#[getter]
+	fn pinned_toolchain(&self) -> Option<&str> {
+		self.pinned_toolchain.as_ref().map(|s| s as &str)
+	}
+}
+
+#[pymethods]
+impl TargetInfo {
+	fn __str__(&self) -> String {
+		format!("{}", self)
+	}
+
+	fn __repr__(&self) -> String {
+		format!("{:?}", self)
+	}
+
+	#[getter]
+	fn arch(&self) -> &str {
+		&self.arch
+	}
+
+	#[getter]
+	fn os(&self) -> &str {
+		&self.os
+	}
+
+	#[getter]
+	fn family(&self) -> Vec<&str> {
+		self.family.iter().map(|s| s as &str).collect()
+	}
+
+	#[getter]
+	fn env(&self) -> &str {
+		&self.env
+	}
+
+	#[getter]
+	fn vendor(&self) -> &str {
+		&self.vendor
+	}
+
+	#[getter]
+	fn endian(&self) -> Endianness {
+		self.endian
+	}
+
+	#[getter]
+	fn pointer_width(&self) -> u8 {
+		self.pointer_width
+	}
+
+	#[getter]
+	fn features(&self) -> Vec<&str> {
+		self.features.iter().map(|s| s as &str).collect()
+	}
 }
 
 #[pymethods]
@@ -187,19 +406,7 @@ impl GitInfo {
 	/// Gets *almost* the timestamp, as Python's `datetime` does not account for leap seconds
 	#[getter]
 	fn commit_timestamp<'py>(&self, py: Python<'py>) -> PyResult<&'py PyAny> {
-		use chrono::{Datelike, Timelike};
-
-		let py_datetime = py.import("datetime")?;
-		py_datetime.getattr("datetime")?.call1((
-			self.commit_timestamp.year_ce().1,
-			self.commit_timestamp.month(),
-			self.commit_timestamp.day(),
-			self.commit_timestamp.hour(),
-			self.commit_timestamp.minute(),
-			self.commit_timestamp.second(),
-			self.commit_timestamp.timestamp_subsec_micros() % 1_000_000,
-			py_datetime.getattr("timezone")?.getattr("utc")?,
-		))
+		to_py_datetime(py, &self.commit_timestamp)
 	}
 
 	#[getter]
@@ -216,6 +423,189 @@ impl GitInfo {
 	fn tags(&self) -> Vec<&str> {
 		self.tags.iter().map(|s| s as &str).collect()
 	}
+
+	#[getter]
+	fn nearest_tag(&self) -> Option<&str> {
+		self.nearest_tag.as_ref().map(|s| s as &str)
+	}
+
+	#[getter]
+	fn commits_since_tag(&self) -> Option<u32> {
+		self.commits_since_tag
+	}
+
+	#[getter]
+	fn nearest_version_tag(&self) -> Option<&str> {
+		self.nearest_version_tag.as_ref().map(|s| s as &str)
+	}
+
+	#[getter]
+	fn commits_since_version_tag(&self) -> Option<u32> {
+		self.commits_since_version_tag
+	}
+
+	#[getter]
+	fn describe(&self) -> &str {
+		&self.describe
+	}
+
+	#[getter]
+	fn crate_tags(&self) -> Vec<&str> {
+		self.crate_tags.iter().map(|s| s as &str).collect()
+	}
+
+	#[getter]
+	fn nearest_crate_tag(&self) -> Option<&str> {
+		self.nearest_crate_tag.as_ref().map(|s| s as &str)
+	}
+
+	#[getter]
+	fn commits_since_crate_tag(&self) -> Option<u32> {
+		self.commits_since_crate_tag
+	}
+
+	#[getter]
+	fn crate_tag_version(&self) -> Option<Version> {
+		self.crate_tag_version.clone().map(Version)
+	}
+
+	#[getter]
+	fn crate_tag_version_matches(&self) -> Option<bool> {
+		self.crate_tag_version_matches
+	}
+
+	#[getter]
+	fn crate_commit_id(&self) -> Option<&str> {
+		self.crate_commit_id.as_ref().map(|s| s as &str)
+	}
+
+	/// Gets *almost* the timestamp, as Python's `datetime` does not account for leap seconds
+	#[getter]
+	fn crate_commit_timestamp<'py>(&self, py: Python<'py>) -> PyResult<Option<&'py PyAny>> {
+		self
+			.crate_commit_timestamp
+			.map(|timestamp| to_py_datetime(py, &timestamp))
+			.transpose()
+	}
+
+	#[getter]
+	fn crate_dirty(&self) -> bool {
+		self.crate_dirty
+	}
+
+	#[getter]
+	fn remotes(&self, py: Python<'_>) -> PyObject {
+		self.remotes.clone().into_py(py)
+	}
+
+	#[getter]
+	fn upstream_remote(&self) -> Option<&str> {
+		self.upstream_remote.as_ref().map(|s| s as &str)
+	}
+
+	#[getter]
+	fn upstream_branch(&self) -> Option<&str> {
+		self.upstream_branch.as_ref().map(|s| s as &str)
+	}
+
+	#[getter]
+	fn commits_ahead(&self) -> Option<u32> {
+		self.commits_ahead
+	}
+
+	#[getter]
+	fn commits_behind(&self) -> Option<u32> {
+		self.commits_behind
+	}
+
+	#[getter]
+	fn author_name(&self) -> Option<&str> {
+		self.author_name.as_ref().map(|s| s as &str)
+	}
+
+	#[getter]
+	fn author_email(&self) -> Option<&str> {
+		self.author_email.as_ref().map(|s| s as &str)
+	}
+
+	#[getter]
+	fn committer_name(&self) -> Option<&str> {
+		self.committer_name.as_ref().map(|s| s as &str)
+	}
+
+	#[getter]
+	fn committer_email(&self) -> Option<&str> {
+		self.committer_email.as_ref().map(|s| s as &str)
+	}
+
+	/// Gets *almost* the timestamp, as Python's `datetime` does not account for leap seconds
+	#[getter]
+	fn author_timestamp<'py>(&self, py: Python<'py>) -> PyResult<Option<&'py PyAny>> {
+		self
+			.author_timestamp
+			.map(|timestamp| to_py_datetime(py, &timestamp))
+			.transpose()
+	}
+
+	/// Gets *almost* the timestamp, as Python's `datetime` does not account for leap seconds
+	#[getter]
+	fn committer_timestamp<'py>(&self, py: Python<'py>) -> PyResult<Option<&'py PyAny>> {
+		self
+			.committer_timestamp
+			.map(|timestamp| to_py_datetime(py, &timestamp))
+			.transpose()
+	}
+
+	#[getter]
+	fn summary(&self) -> Option<&str> {
+		self.summary.as_ref().map(|s| s as &str)
+	}
+
+	#[getter]
+	fn trailers(&self, py: Python<'_>) -> PyObject {
+		self.trailers.clone().into_py(py)
+	}
+
+	#[getter]
+	fn submodules(&self) -> Vec<SubmoduleInfo> {
+		self.submodules.clone()
+	}
+}
+
+#[pymethods]
+impl SubmoduleInfo {
+	fn __str__(&self) -> String {
+		format!("{}", self)
+	}
+
+	fn __repr__(&self) -> String {
+		format!("{:?}", self)
+	}
+
+	#[getter]
+	fn path(&self) -> &str {
+		&self.path
+	}
+
+	#[getter]
+	fn commit_id(&self) -> Option<&str> {
+		self.commit_id.as_ref().map(|s| s as &str)
+	}
+
+	#[getter]
+	fn recorded_commit_id(&self) -> Option<&str> {
+		self.recorded_commit_id.as_ref().map(|s| s as &str)
+	}
+
+	#[getter]
+	fn matches_superproject(&self) -> bool {
+		self.matches_superproject
+	}
+
+	#[getter]
+	fn dirty(&self) -> bool {
+		self.dirty
+	}
 }
 
 #[pyclass]
@@ -256,3 +646,111 @@ impl Version {
 		self.0.build.to_string()
 	}
 }
+
+#[pymethods]
+impl MercurialInfo {
+	fn __str__(&self) -> String {
+		format!("{}", self)
+	}
+
+	fn __repr__(&self) -> String {
+		format!("{:?}", self)
+	}
+
+	#[getter]
+	fn changeset_id(&self) -> &str {
+		&self.changeset_id
+	}
+
+	#[getter]
+	fn revision(&self) -> u32 {
+		self.revision
+	}
+
+	#[getter]
+	fn branch(&self) -> &str {
+		&self.branch
+	}
+
+	#[getter]
+	fn bookmarks(&self) -> Vec<&str> {
+		self.bookmarks.iter().map(|s| s as &str).collect()
+	}
+
+	#[getter]
+	fn tags(&self) -> Vec<&str> {
+		self.tags.iter().map(|s| s as &str).collect()
+	}
+
+	#[getter]
+	fn dirty(&self) -> bool {
+		self.dirty
+	}
+}
+
+#[pymethods]
+impl SubversionInfo {
+	fn __str__(&self) -> String {
+		format!("{}", self)
+	}
+
+	fn __repr__(&self) -> String {
+		format!("{:?}", self)
+	}
+
+	#[getter]
+	fn url(&self) -> &str {
+		&self.url
+	}
+
+	#[getter]
+	fn revision(&self) -> u32 {
+		self.revision
+	}
+
+	#[getter]
+	fn last_changed_revision(&self) -> u32 {
+		self.last_changed_revision
+	}
+
+	#[getter]
+	fn last_changed_author(&self) -> Option<&str> {
+		self.last_changed_author.as_ref().map(|s| s as &str)
+	}
+
+	#[getter]
+	fn modified(&self) -> bool {
+		self.modified
+	}
+}
+
+#[pymethods]
+impl CargoVcsInfo {
+	fn __str__(&self) -> String {
+		format!("{}", self)
+	}
+
+	fn __repr__(&self) -> String {
+		format!("{:?}", self)
+	}
+
+	#[getter]
+	fn commit_id(&self) -> &str {
+		&self.commit_id
+	}
+
+	#[getter]
+	fn commit_short_id(&self) -> &str {
+		&self.commit_short_id
+	}
+
+	#[getter]
+	fn dirty(&self) -> bool {
+		self.dirty
+	}
+
+	#[getter]
+	fn path_in_vcs(&self) -> &str {
+		&self.path_in_vcs
+	}
+}
@@ -9,17 +9,28 @@ pub(crate) enum Type {
 
 	BuildInfo,
 	OptimizationLevel,
+	ProfileSettings,
+	PanicStrategy,
 	CrateInfo,
 	CompilerInfo,
 	CompilerChannel,
+	TargetInfo,
+	Endianness,
 	VersionControl,
 	GitInfo,
+	MercurialInfo,
+	SubversionInfo,
+	CargoVcsInfo,
+	SubmoduleInfo,
+	CustomValue,
 
 	Version,
 	DateTimeUtc,
+	DateTimeFixedOffset,
 
 	Option,
 	Vec,
+	Map,
 }
 
 use std::fmt;
@@ -33,17 +44,28 @@ impl fmt::Display for Type {
 
 			Type::BuildInfo => write!(f, "build_info::BuildInfo"),
 			Type::OptimizationLevel => write!(f, "build_info::OptimizationLevel"),
+			Type::ProfileSettings => write!(f, "build_info::ProfileSettings"),
+			Type::PanicStrategy => write!(f, "build_info::PanicStrategy"),
 			Type::CrateInfo => write!(f, "build_info::CrateInfo"),
 			Type::CompilerInfo => write!(f, "build_info::CompilerInfo"),
 			Type::CompilerChannel => write!(f, "build_info::CompilerChannel"),
+			Type::TargetInfo => write!(f, "build_info::TargetInfo"),
+			Type::Endianness => write!(f, "build_info::Endianness"),
 			Type::VersionControl => write!(f, "build_info::VersionControl"),
 			Type::GitInfo => write!(f, "build_info::GitInfo"),
+			Type::MercurialInfo => write!(f, "build_info::MercurialInfo"),
+			Type::SubversionInfo => write!(f, "build_info::SubversionInfo"),
+			Type::CargoVcsInfo => write!(f, "build_info::CargoVcsInfo"),
+			Type::SubmoduleInfo => write!(f, "build_info::SubmoduleInfo"),
+			Type::CustomValue => write!(f, "build_info::CustomValue"),
 
 			Type::Version => write!(f, "build_info::semver::Version"),
 			Type::DateTimeUtc => write!(f, "build_info::chrono::DateTime<build_info::chrono::Utc>"),
+			Type::DateTimeFixedOffset => write!(f, "build_info::chrono::DateTime<build_info::chrono::FixedOffset>"),
 
 			Type::Option => write!(f, "Option<_>"),
 			Type::Vec => write!(f, "Vec<_>"),
+			Type::Map => write!(f, "BTreeMap<String, _>"),
 		}
 	}
 }
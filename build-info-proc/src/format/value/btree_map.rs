@@ -0,0 +1,107 @@
+use std::{any::Any, collections::BTreeMap};
+
+use anyhow::anyhow;
+use num_bigint::BigInt;
+
+use super::{
+	as_arguments_0, as_field_name, as_simple_arguments_1, FormatSpecifier, Type, Value, OP_ARRAY_INDEX, OP_FIELD_ACCESS,
+};
+
+impl<T: 'static + Value + Clone> Value for BTreeMap<String, T> {
+	fn call(&self, func: &str, args: &[Box<dyn Value>]) -> anyhow::Result<Box<dyn Value>> {
+		match func {
+			OP_FIELD_ACCESS => match self.get(as_field_name(args)) {
+				Some(value) => Ok(Box::new(value.clone())),
+				None => self.call_base(func, args),
+			},
+			OP_ARRAY_INDEX => {
+				let (key,) = as_simple_arguments_1::<String>(args)?;
+				self
+					.get(key)
+					.map(|value| Box::new(value.clone()) as Box<dyn Value>)
+					.ok_or_else(|| anyhow!("The key {:?} does not exist", key))
+			}
+			"contains_key" => {
+				let (key,) = as_simple_arguments_1::<String>(args)?;
+				Ok(Box::new(self.contains_key(key)))
+			}
+			"get" => {
+				let (key,) = as_simple_arguments_1::<String>(args)?;
+				Ok(Box::new(self.get(key).cloned()))
+			}
+			"is_empty" => {
+				as_arguments_0(args)?;
+				Ok(Box::new(self.is_empty()))
+			}
+			"len" => {
+				as_arguments_0(args)?;
+				Ok(Box::new(BigInt::from(self.len())))
+			}
+			_ => self.call_base(func, args),
+		}
+	}
+
+	fn get_type(&self) -> Type {
+		Type::Map
+	}
+
+	fn as_any(&self) -> &dyn Any {
+		self
+	}
+
+	fn format(&self, buffer: &mut String, spec: FormatSpecifier) {
+		use std::fmt::Write;
+
+		match spec {
+			FormatSpecifier::Default => {
+				*buffer += "{";
+				for (i, (key, value)) in self.iter().enumerate() {
+					if i > 0 {
+						*buffer += ", ";
+					}
+					*buffer += key;
+					*buffer += ": ";
+					value.format(buffer, spec);
+				}
+				*buffer += "}";
+			}
+			FormatSpecifier::Debug => write!(buffer, "{self:?}").unwrap(),
+			FormatSpecifier::DebugAlt => write!(buffer, "{self:#?}").unwrap(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use pretty_assertions::assert_eq;
+
+	use super::*;
+
+	fn sample() -> BTreeMap<String, Option<String>> {
+		let mut map = BTreeMap::new();
+		map.insert("CI_PIPELINE_ID".to_string(), Some("1234".to_string()));
+		map.insert("DEPLOY_ENV".to_string(), None);
+		map
+	}
+
+	#[test]
+	fn field_access() {
+		let map = sample();
+
+		let mut buff = String::new();
+		map
+			.call(OP_FIELD_ACCESS, &[Box::new("CI_PIPELINE_ID".to_string())])
+			.unwrap()
+			.format(&mut buff, FormatSpecifier::Default);
+		assert_eq!(buff, "1234");
+
+		assert!(map.call(OP_FIELD_ACCESS, &[Box::new("HOME".to_string())]).is_err());
+	}
+
+	#[test]
+	fn format_default() {
+		let mut buff = String::new();
+		Value::format(&sample(), &mut buff, FormatSpecifier::Default);
+		assert_eq!(buff, "{CI_PIPELINE_ID: 1234, DEPLOY_ENV: None}");
+	}
+}
@@ -11,9 +11,12 @@ impl Value for BuildInfo {
 				"timestamp" => Ok(Box::new(self.timestamp)),
 				"profile" => Ok(Box::new(self.profile.clone())),
 				"optimization_level" => Ok(Box::new(self.optimization_level)),
+				"profile_settings" => Ok(Box::new(self.profile_settings.clone())),
 				"crate_info" => Ok(Box::new(self.crate_info.clone())),
 				"compiler" => Ok(Box::new(self.compiler.clone())),
 				"version_control" => Ok(Box::new(self.version_control.clone())),
+				"custom" => Ok(Box::new(self.custom.clone())),
+				"environment" => Ok(Box::new(self.environment.clone())),
 				_ => self.call_base(func, args),
 			},
 			"to_string" => {
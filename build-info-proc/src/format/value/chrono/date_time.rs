@@ -1,6 +1,6 @@
 use std::any::Any;
 
-use build_info_common::chrono::{DateTime, Utc};
+use build_info_common::chrono::{DateTime, FixedOffset, Utc};
 
 use super::super::{as_arguments_0, as_simple_arguments_1, FormatSpecifier, Type, Value};
 
@@ -37,3 +37,37 @@ impl Value for DateTime<Utc> {
 		}
 	}
 }
+
+impl Value for DateTime<FixedOffset> {
+	fn call(&self, func: &str, args: &[Box<dyn Value>]) -> anyhow::Result<Box<dyn Value>> {
+		match func {
+			"format" => {
+				let (format_string,) = as_simple_arguments_1::<String>(args)?;
+				Ok(Box::new(self.format(format_string).to_string()))
+			}
+			"to_string" => {
+				as_arguments_0(args)?;
+				Ok(Box::new(self.format("%Y-%m-%d %H:%M:%S%:z").to_string()))
+			}
+			_ => self.call_base(func, args),
+		}
+	}
+
+	fn get_type(&self) -> Type {
+		Type::DateTimeFixedOffset
+	}
+
+	fn as_any(&self) -> &dyn Any {
+		self
+	}
+
+	fn format(&self, buffer: &mut String, spec: FormatSpecifier) {
+		use std::fmt::Write;
+
+		match spec {
+			FormatSpecifier::Default => write!(buffer, "{}", self.format("%Y-%m-%d %H:%M:%S%:z")).unwrap(),
+			FormatSpecifier::Debug => write!(buffer, "{self:?}").unwrap(),
+			FormatSpecifier::DebugAlt => write!(buffer, "{self:#?}").unwrap(),
+		}
+	}
+}
@@ -14,6 +14,20 @@ impl Value for CompilerInfo {
 				"channel" => Ok(Box::new(self.channel)),
 				"host_triple" => Ok(Box::new(self.host_triple.clone())),
 				"target_triple" => Ok(Box::new(self.target_triple.clone())),
+				"target" => Ok(Box::new(self.target.clone())),
+				"rustflags" => Ok(Box::new(self.rustflags.clone())),
+				"rustc_wrapper" => Ok(Box::new(self.rustc_wrapper.clone())),
+				"rustc_workspace_wrapper" => Ok(Box::new(self.rustc_workspace_wrapper.clone())),
+				"linker" => Ok(Box::new(self.linker.clone())),
+				"target_cpu" => Ok(Box::new(self.target_cpu.clone())),
+				"sanitizers" => Ok(Box::new(self.sanitizers.clone())),
+				"instrument_coverage" => Ok(Box::new(self.instrument_coverage)),
+				"profile_generate" => Ok(Box::new(self.profile_generate)),
+				"profile_use" => Ok(Box::new(self.profile_use)),
+				"llvm_version" => Ok(Box::new(self.llvm_version.clone())),
+				"cargo_version" => Ok(Box::new(self.cargo_version.clone())),
+				"rustup_toolchain" => Ok(Box::new(self.rustup_toolchain.clone())),
+				"pinned_toolchain" => Ok(Box::new(self.pinned_toolchain.clone())),
 				_ => self.call_base(func, args),
 			},
 			"to_string" => {
@@ -10,11 +10,14 @@ impl Value for CrateInfo {
 			OP_FIELD_ACCESS => match as_field_name(args) {
 				"name" => Ok(Box::new(self.name.clone())),
 				"version" => Ok(Box::new(self.version.clone())),
+				"git_version" => Ok(Box::new(self.git_version.clone())),
 				"authors" => Ok(Box::new(self.authors.clone())),
 				"license" => Ok(Box::new(self.license.clone())),
 				"enabled_features" => Ok(Box::new(self.enabled_features.clone())),
 				"available_features" => Ok(Box::new(self.available_features.clone())),
 				"dependencies" => Ok(Box::new(self.dependencies.clone())),
+				"workspace_root" => Ok(Box::new(self.workspace_root.clone())),
+				"path_in_workspace" => Ok(Box::new(self.path_in_workspace.clone())),
 				_ => self.call_base(func, args),
 			},
 			"to_string" => {
@@ -0,0 +1,90 @@
+use std::any::Any;
+
+use build_info_common::CustomValue;
+use num_bigint::BigInt;
+
+use super::{FormatSpecifier, Type, Value};
+
+/// Unpacks a `CustomValue` into the corresponding `format!` value, so that e.g. strings behave like any other string.
+fn unpack(value: &CustomValue) -> Box<dyn Value> {
+	match value {
+		CustomValue::Bool(value) => Box::new(*value),
+		CustomValue::Integer(value) => Box::new(BigInt::from(*value)),
+		CustomValue::String(value) => Box::new(value.clone()),
+		CustomValue::Map(value) => Box::new(value.clone()),
+	}
+}
+
+impl Value for CustomValue {
+	fn call(&self, func: &str, args: &[Box<dyn Value>]) -> anyhow::Result<Box<dyn Value>> {
+		unpack(self).call(func, args)
+	}
+
+	fn get_type(&self) -> Type {
+		Type::CustomValue
+	}
+
+	fn as_any(&self) -> &dyn Any {
+		self
+	}
+
+	fn format(&self, buffer: &mut String, spec: FormatSpecifier) {
+		use std::fmt::Write;
+
+		match spec {
+			FormatSpecifier::Default => unpack(self).format(buffer, spec),
+			FormatSpecifier::Debug => write!(buffer, "{self:?}").unwrap(),
+			FormatSpecifier::DebugAlt => write!(buffer, "{self:#?}").unwrap(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use std::collections::BTreeMap;
+
+	use pretty_assertions::assert_eq;
+
+	use super::{super::OP_FIELD_ACCESS, *};
+
+	fn sample() -> BTreeMap<String, CustomValue> {
+		let mut nested = BTreeMap::new();
+		nested.insert("enabled".to_string(), CustomValue::Bool(true));
+
+		let mut map = BTreeMap::new();
+		map.insert("deploy_channel".to_string(), CustomValue::from("canary"));
+		map.insert("replicas".to_string(), CustomValue::Integer(3));
+		map.insert("nested".to_string(), CustomValue::Map(nested));
+		map
+	}
+
+	#[test]
+	fn field_access() {
+		let map = sample();
+
+		let mut buff = String::new();
+		map
+			.call(OP_FIELD_ACCESS, &[Box::new("deploy_channel".to_string())])
+			.unwrap()
+			.format(&mut buff, FormatSpecifier::Default);
+		assert_eq!(buff, "canary");
+
+		buff.clear();
+		map
+			.call(OP_FIELD_ACCESS, &[Box::new("nested".to_string())])
+			.unwrap()
+			.call(OP_FIELD_ACCESS, &[Box::new("enabled".to_string())])
+			.unwrap()
+			.format(&mut buff, FormatSpecifier::Default);
+		assert_eq!(buff, "true");
+
+		assert!(map.call(OP_FIELD_ACCESS, &[Box::new("missing".to_string())]).is_err());
+	}
+
+	#[test]
+	fn format_default() {
+		let mut buff = String::new();
+		Value::format(&sample(), &mut buff, FormatSpecifier::Default);
+		assert_eq!(buff, "{deploy_channel: canary, nested: {enabled: true}, replicas: 3}");
+	}
+}
@@ -1,6 +1,7 @@
 use std::any::Any;
 
 use build_info_common::GitInfo;
+use num_bigint::BigInt;
 
 use super::{as_arguments_0, as_field_name, FormatSpecifier, Type, Value};
 
@@ -14,6 +15,33 @@ impl Value for GitInfo {
 				"dirty" => Ok(Box::new(self.dirty)),
 				"branch" => Ok(Box::new(self.branch.clone())),
 				"tags" => Ok(Box::new(self.tags.clone())),
+				"nearest_tag" => Ok(Box::new(self.nearest_tag.clone())),
+				"commits_since_tag" => Ok(Box::new(self.commits_since_tag.map(BigInt::from))),
+				"nearest_version_tag" => Ok(Box::new(self.nearest_version_tag.clone())),
+				"commits_since_version_tag" => Ok(Box::new(self.commits_since_version_tag.map(BigInt::from))),
+				"describe" => Ok(Box::new(self.describe.clone())),
+				"crate_tags" => Ok(Box::new(self.crate_tags.clone())),
+				"nearest_crate_tag" => Ok(Box::new(self.nearest_crate_tag.clone())),
+				"commits_since_crate_tag" => Ok(Box::new(self.commits_since_crate_tag.map(BigInt::from))),
+				"crate_tag_version" => Ok(Box::new(self.crate_tag_version.clone())),
+				"crate_tag_version_matches" => Ok(Box::new(self.crate_tag_version_matches)),
+				"crate_commit_id" => Ok(Box::new(self.crate_commit_id.clone())),
+				"crate_commit_timestamp" => Ok(Box::new(self.crate_commit_timestamp)),
+				"crate_dirty" => Ok(Box::new(self.crate_dirty)),
+				"remotes" => Ok(Box::new(self.remotes.clone())),
+				"upstream_remote" => Ok(Box::new(self.upstream_remote.clone())),
+				"upstream_branch" => Ok(Box::new(self.upstream_branch.clone())),
+				"commits_ahead" => Ok(Box::new(self.commits_ahead.map(BigInt::from))),
+				"commits_behind" => Ok(Box::new(self.commits_behind.map(BigInt::from))),
+				"author_name" => Ok(Box::new(self.author_name.clone())),
+				"author_email" => Ok(Box::new(self.author_email.clone())),
+				"committer_name" => Ok(Box::new(self.committer_name.clone())),
+				"committer_email" => Ok(Box::new(self.committer_email.clone())),
+				"author_timestamp" => Ok(Box::new(self.author_timestamp)),
+				"committer_timestamp" => Ok(Box::new(self.committer_timestamp)),
+				"summary" => Ok(Box::new(self.summary.clone())),
+				"trailers" => Ok(Box::new(self.trailers.clone())),
+				"submodules" => Ok(Box::new(self.submodules.clone())),
 				_ => self.call_base(func, args),
 			},
 			"to_string" => {
@@ -0,0 +1,45 @@
+use std::any::Any;
+
+use build_info_common::MercurialInfo;
+use num_bigint::BigInt;
+
+use super::{as_arguments_0, as_field_name, FormatSpecifier, Type, Value, OP_FIELD_ACCESS};
+
+impl Value for MercurialInfo {
+	fn call(&self, func: &str, args: &[Box<dyn Value>]) -> anyhow::Result<Box<dyn Value>> {
+		match func {
+			OP_FIELD_ACCESS => match as_field_name(args) {
+				"changeset_id" => Ok(Box::new(self.changeset_id.clone())),
+				"revision" => Ok(Box::new(BigInt::from(self.revision))),
+				"branch" => Ok(Box::new(self.branch.clone())),
+				"bookmarks" => Ok(Box::new(self.bookmarks.clone())),
+				"tags" => Ok(Box::new(self.tags.clone())),
+				"dirty" => Ok(Box::new(self.dirty)),
+				_ => self.call_base(func, args),
+			},
+			"to_string" => {
+				as_arguments_0(args)?;
+				Ok(Box::new(self.to_string()))
+			}
+			_ => self.call_base(func, args),
+		}
+	}
+
+	fn get_type(&self) -> Type {
+		Type::MercurialInfo
+	}
+
+	fn as_any(&self) -> &dyn Any {
+		self
+	}
+
+	fn format(&self, buffer: &mut String, spec: FormatSpecifier) {
+		use std::fmt::Write;
+
+		match spec {
+			FormatSpecifier::Default => write!(buffer, "{self}").unwrap(),
+			FormatSpecifier::Debug => write!(buffer, "{self:?}").unwrap(),
+			FormatSpecifier::DebugAlt => write!(buffer, "{self:#?}").unwrap(),
+		}
+	}
+}
@@ -10,6 +10,7 @@ use num_traits::ToPrimitive;
 use super::Type;
 
 mod bool;
+mod btree_map;
 mod char;
 mod int;
 mod option;
@@ -20,11 +21,20 @@ mod chrono;
 mod semver;
 
 mod build_info;
+mod cargo_vcs_info;
 mod compiler_channel;
 mod compiler_info;
 mod crate_info;
+mod custom;
+mod endianness;
 mod git_info;
+mod mercurial_info;
 mod optimization_level;
+mod panic_strategy;
+mod profile_settings;
+mod submodule_info;
+mod subversion_info;
+mod target_info;
 mod version_control;
 
 mod functions;
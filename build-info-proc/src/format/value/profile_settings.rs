@@ -0,0 +1,45 @@
+use std::any::Any;
+
+use build_info_common::ProfileSettings;
+use num_bigint::BigInt;
+
+use super::{as_arguments_0, as_field_name, FormatSpecifier, Type, Value, OP_FIELD_ACCESS};
+
+impl Value for ProfileSettings {
+	fn call(&self, func: &str, args: &[Box<dyn Value>]) -> anyhow::Result<Box<dyn Value>> {
+		match func {
+			OP_FIELD_ACCESS => match as_field_name(args) {
+				"debug_assertions" => Ok(Box::new(self.debug_assertions)),
+				"overflow_checks" => Ok(Box::new(self.overflow_checks)),
+				"panic" => Ok(Box::new(self.panic)),
+				"lto" => Ok(Box::new(self.lto.clone())),
+				"codegen_units" => Ok(Box::new(BigInt::from(self.codegen_units))),
+				"debuginfo" => Ok(Box::new(self.debuginfo.clone())),
+				_ => self.call_base(func, args),
+			},
+			"to_string" => {
+				as_arguments_0(args)?;
+				Ok(Box::new(self.to_string()))
+			}
+			_ => self.call_base(func, args),
+		}
+	}
+
+	fn get_type(&self) -> Type {
+		Type::ProfileSettings
+	}
+
+	fn as_any(&self) -> &dyn Any {
+		self
+	}
+
+	fn format(&self, buffer: &mut String, spec: FormatSpecifier) {
+		use std::fmt::Write;
+
+		match spec {
+			FormatSpecifier::Default => write!(buffer, "{self}").unwrap(),
+			FormatSpecifier::Debug => write!(buffer, "{self:?}").unwrap(),
+			FormatSpecifier::DebugAlt => write!(buffer, "{self:#?}").unwrap(),
+		}
+	}
+}
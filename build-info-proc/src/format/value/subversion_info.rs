@@ -0,0 +1,44 @@
+use std::any::Any;
+
+use build_info_common::SubversionInfo;
+use num_bigint::BigInt;
+
+use super::{as_arguments_0, as_field_name, FormatSpecifier, Type, Value, OP_FIELD_ACCESS};
+
+impl Value for SubversionInfo {
+	fn call(&self, func: &str, args: &[Box<dyn Value>]) -> anyhow::Result<Box<dyn Value>> {
+		match func {
+			OP_FIELD_ACCESS => match as_field_name(args) {
+				"url" => Ok(Box::new(self.url.clone())),
+				"revision" => Ok(Box::new(BigInt::from(self.revision))),
+				"last_changed_revision" => Ok(Box::new(BigInt::from(self.last_changed_revision))),
+				"last_changed_author" => Ok(Box::new(self.last_changed_author.clone())),
+				"modified" => Ok(Box::new(self.modified)),
+				_ => self.call_base(func, args),
+			},
+			"to_string" => {
+				as_arguments_0(args)?;
+				Ok(Box::new(self.to_string()))
+			}
+			_ => self.call_base(func, args),
+		}
+	}
+
+	fn get_type(&self) -> Type {
+		Type::SubversionInfo
+	}
+
+	fn as_any(&self) -> &dyn Any {
+		self
+	}
+
+	fn format(&self, buffer: &mut String, spec: FormatSpecifier) {
+		use std::fmt::Write;
+
+		match spec {
+			FormatSpecifier::Default => write!(buffer, "{self}").unwrap(),
+			FormatSpecifier::Debug => write!(buffer, "{self:?}").unwrap(),
+			FormatSpecifier::DebugAlt => write!(buffer, "{self:#?}").unwrap(),
+		}
+	}
+}
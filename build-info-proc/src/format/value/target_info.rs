@@ -0,0 +1,47 @@
+use std::any::Any;
+
+use build_info_common::TargetInfo;
+use num_bigint::BigInt;
+
+use super::{as_arguments_0, as_field_name, FormatSpecifier, Type, Value, OP_FIELD_ACCESS};
+
+impl Value for TargetInfo {
+	fn call(&self, func: &str, args: &[Box<dyn Value>]) -> anyhow::Result<Box<dyn Value>> {
+		match func {
+			OP_FIELD_ACCESS => match as_field_name(args) {
+				"arch" => Ok(Box::new(self.arch.clone())),
+				"os" => Ok(Box::new(self.os.clone())),
+				"family" => Ok(Box::new(self.family.clone())),
+				"env" => Ok(Box::new(self.env.clone())),
+				"vendor" => Ok(Box::new(self.vendor.clone())),
+				"endian" => Ok(Box::new(self.endian)),
+				"pointer_width" => Ok(Box::new(BigInt::from(self.pointer_width))),
+				"features" => Ok(Box::new(self.features.clone())),
+				_ => self.call_base(func, args),
+			},
+			"to_string" => {
+				as_arguments_0(args)?;
+				Ok(Box::new(self.to_string()))
+			}
+			_ => self.call_base(func, args),
+		}
+	}
+
+	fn get_type(&self) -> Type {
+		Type::TargetInfo
+	}
+
+	fn as_any(&self) -> &dyn Any {
+		self
+	}
+
+	fn format(&self, buffer: &mut String, spec: FormatSpecifier) {
+		use std::fmt::Write;
+
+		match spec {
+			FormatSpecifier::Default => write!(buffer, "{self}").unwrap(),
+			FormatSpecifier::Debug => write!(buffer, "{self:?}").unwrap(),
+			FormatSpecifier::DebugAlt => write!(buffer, "{self:#?}").unwrap(),
+		}
+	}
+}
@@ -11,6 +11,18 @@ impl Value for VersionControl {
 				as_arguments_0(args)?;
 				Ok(Box::new(self.git().cloned()))
 			}
+			"mercurial" => {
+				as_arguments_0(args)?;
+				Ok(Box::new(self.mercurial().cloned()))
+			}
+			"subversion" => {
+				as_arguments_0(args)?;
+				Ok(Box::new(self.subversion().cloned()))
+			}
+			"cargo_vcs_info" => {
+				as_arguments_0(args)?;
+				Ok(Box::new(self.cargo_vcs_info().cloned()))
+			}
 			"to_string" => {
 				as_arguments_0(args)?;
 				Ok(Box::new(self.to_string()))
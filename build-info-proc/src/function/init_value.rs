@@ -1,7 +1,10 @@
+use std::collections::BTreeMap;
+
 use build_info_common::{
-	chrono::{DateTime, Datelike, NaiveDate, Utc},
+	chrono::{DateTime, Datelike, FixedOffset, NaiveDate, Utc},
 	semver::Version,
-	BuildInfo, CompilerChannel, CompilerInfo, CrateInfo, GitInfo, OptimizationLevel, VersionControl,
+	BuildInfo, CargoVcsInfo, CompilerChannel, CompilerInfo, CrateInfo, CustomValue, Endianness, GitInfo, MercurialInfo,
+	OptimizationLevel, PanicStrategy, ProfileSettings, SubmoduleInfo, SubversionInfo, TargetInfo, VersionControl,
 };
 use proc_macro2::{Delimiter, Group, Ident, TokenStream};
 use quote::{quote, quote_spanned, TokenStreamExt};
@@ -31,6 +34,10 @@ impl InitValue for BuildInfo {
 		init_value(&self.optimization_level, &mut initializer, definition_crate);
 		initializer.append_all(quote!(,));
 
+		initializer.append_all(quote!(profile_settings:));
+		init_value(&self.profile_settings, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
 		initializer.append_all(quote!(crate_info:));
 		init_value(&self.crate_info, &mut initializer, definition_crate);
 		initializer.append_all(quote!(,));
@@ -43,6 +50,14 @@ impl InitValue for BuildInfo {
 		init_value(&self.version_control, &mut initializer, definition_crate);
 		initializer.append_all(quote!(,));
 
+		initializer.append_all(quote!(custom:));
+		init_value(&self.custom, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(environment:));
+		init_value(&self.environment, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
 		tokens.append(Group::new(Delimiter::Brace, initializer));
 	}
 }
@@ -66,6 +81,50 @@ impl InitValue for OptimizationLevel {
 	}
 }
 
+impl InitValue for ProfileSettings {
+	fn init_value(&self, tokens: &mut TokenStream, definition_crate: &Ident) {
+		tokens.append_all(quote_spanned!(proc_macro::Span::mixed_site().into() => #definition_crate::ProfileSettings));
+		let mut initializer = TokenStream::new();
+
+		initializer.append_all(quote!(debug_assertions:));
+		init_value(&self.debug_assertions, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(overflow_checks:));
+		init_value(&self.overflow_checks, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(panic:));
+		init_value(&self.panic, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(lto:));
+		init_value(&self.lto, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(codegen_units:));
+		init_value(&self.codegen_units, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(debuginfo:));
+		init_value(&self.debuginfo, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		tokens.append(Group::new(Delimiter::Brace, initializer));
+	}
+}
+
+impl InitValue for PanicStrategy {
+	fn init_value(&self, tokens: &mut TokenStream, definition_crate: &Ident) {
+		match self {
+			PanicStrategy::Unwind => tokens
+				.append_all(quote_spanned!(proc_macro::Span::mixed_site().into() => #definition_crate::PanicStrategy::Unwind)),
+			PanicStrategy::Abort => tokens
+				.append_all(quote_spanned!(proc_macro::Span::mixed_site().into() => #definition_crate::PanicStrategy::Abort)),
+		};
+	}
+}
+
 impl InitValue for CrateInfo {
 	fn init_value(&self, tokens: &mut TokenStream, definition_crate: &Ident) {
 		tokens.append_all(quote_spanned!(proc_macro::Span::mixed_site().into() => #definition_crate::CrateInfo));
@@ -79,6 +138,10 @@ impl InitValue for CrateInfo {
 		init_value(&self.version, &mut initializer, definition_crate);
 		initializer.append_all(quote!(,));
 
+		initializer.append_all(quote!(git_version:));
+		init_value(&self.git_version, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
 		initializer.append_all(quote!(authors:));
 		init_value(&self.authors, &mut initializer, definition_crate);
 		initializer.append_all(quote!(,));
@@ -99,6 +162,14 @@ impl InitValue for CrateInfo {
 		init_value(&self.dependencies, &mut initializer, definition_crate);
 		initializer.append_all(quote!(,));
 
+		initializer.append_all(quote!(workspace_root:));
+		init_value(&self.workspace_root, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(path_in_workspace:));
+		init_value(&self.path_in_workspace, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
 		tokens.append(Group::new(Delimiter::Brace, initializer));
 	}
 }
@@ -132,10 +203,119 @@ impl InitValue for CompilerInfo {
 		init_value(&self.target_triple, &mut initializer, definition_crate);
 		initializer.append_all(quote!(,));
 
+		initializer.append_all(quote!(target:));
+		init_value(&self.target, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(rustflags:));
+		init_value(&self.rustflags, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(rustc_wrapper:));
+		init_value(&self.rustc_wrapper, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(rustc_workspace_wrapper:));
+		init_value(&self.rustc_workspace_wrapper, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(linker:));
+		init_value(&self.linker, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(target_cpu:));
+		init_value(&self.target_cpu, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(sanitizers:));
+		init_value(&self.sanitizers, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(instrument_coverage:));
+		init_value(&self.instrument_coverage, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(profile_generate:));
+		init_value(&self.profile_generate, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(profile_use:));
+		init_value(&self.profile_use, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(llvm_version:));
+		init_value(&self.llvm_version, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(cargo_version:));
+		init_value(&self.cargo_version, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(rustup_toolchain:));
+		init_value(&self.rustup_toolchain, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(pinned_toolchain:));
+		init_value(&self.pinned_toolchain, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
 		tokens.append(Group::new(Delimiter::Brace, initializer));
 	}
 }
 
+impl InitValue for TargetInfo {
+	fn init_value(&self, tokens: &mut TokenStream, definition_crate: &Ident) {
+		tokens.append_all(quote_spanned!(proc_macro::Span::mixed_site().into() => #definition_crate::TargetInfo));
+		let mut initializer = TokenStream::new();
+
+		initializer.append_all(quote!(arch:));
+		init_value(&self.arch, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(os:));
+		init_value(&self.os, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(family:));
+		init_value(&self.family, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(env:));
+		init_value(&self.env, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(vendor:));
+		init_value(&self.vendor, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(endian:));
+		init_value(&self.endian, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(pointer_width:));
+		init_value(&self.pointer_width, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(features:));
+		init_value(&self.features, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		tokens.append(Group::new(Delimiter::Brace, initializer));
+	}
+}
+
+impl InitValue for Endianness {
+	fn init_value(&self, tokens: &mut TokenStream, definition_crate: &Ident) {
+		match self {
+			Endianness::Big => {
+				tokens.append_all(quote_spanned!(proc_macro::Span::mixed_site().into() => #definition_crate::Endianness::Big))
+			}
+			Endianness::Little => tokens
+				.append_all(quote_spanned!(proc_macro::Span::mixed_site().into() => #definition_crate::Endianness::Little)),
+		};
+	}
+}
+
 impl InitValue for CompilerChannel {
 	fn init_value(&self, tokens: &mut TokenStream, definition_crate: &Ident) {
 		match self {
@@ -185,6 +365,58 @@ impl<T: InitValue> InitValue for Vec<T> {
 	}
 }
 
+impl<T: InitValue> InitValue for BTreeMap<String, T> {
+	fn init_value(&self, tokens: &mut TokenStream, definition_crate: &Ident) {
+		if self.is_empty() {
+			tokens.append_all(quote_spanned!(proc_macro::Span::mixed_site().into() => ::std::collections::BTreeMap::new()));
+			return;
+		}
+
+		let mut initializer = TokenStream::new();
+		for (key, value) in self {
+			let mut value_tokens = TokenStream::new();
+			init_value(value, &mut value_tokens, definition_crate);
+			initializer.append_all(quote_spanned!(proc_macro::Span::mixed_site().into() =>
+				map.insert(#key.to_string(), #value_tokens);
+			));
+		}
+
+		tokens.append_all(quote_spanned!(proc_macro::Span::mixed_site().into() => {
+			let mut map = ::std::collections::BTreeMap::new();
+			#initializer
+			map
+		}));
+	}
+}
+
+impl InitValue for CustomValue {
+	fn init_value(&self, tokens: &mut TokenStream, definition_crate: &Ident) {
+		let mut initializer = TokenStream::new();
+		match self {
+			CustomValue::Bool(value) => {
+				tokens
+					.append_all(quote_spanned!(proc_macro::Span::mixed_site().into() => #definition_crate::CustomValue::Bool));
+				init_value(value, &mut initializer, definition_crate);
+			}
+			CustomValue::Integer(value) => {
+				tokens
+					.append_all(quote_spanned!(proc_macro::Span::mixed_site().into() => #definition_crate::CustomValue::Integer));
+				init_value(value, &mut initializer, definition_crate);
+			}
+			CustomValue::String(value) => {
+				tokens
+					.append_all(quote_spanned!(proc_macro::Span::mixed_site().into() => #definition_crate::CustomValue::String));
+				init_value(value, &mut initializer, definition_crate);
+			}
+			CustomValue::Map(value) => {
+				tokens.append_all(quote_spanned!(proc_macro::Span::mixed_site().into() => #definition_crate::CustomValue::Map));
+				init_value(value, &mut initializer, definition_crate);
+			}
+		}
+		tokens.append(Group::new(Delimiter::Parenthesis, initializer));
+	}
+}
+
 impl InitValue for VersionControl {
 	fn init_value(&self, tokens: &mut TokenStream, definition_crate: &Ident) {
 		match self {
@@ -195,6 +427,30 @@ impl InitValue for VersionControl {
 				init_value(data, &mut initializer, definition_crate);
 				tokens.append(Group::new(Delimiter::Parenthesis, initializer));
 			}
+			VersionControl::Mercurial(data) => {
+				tokens.append_all(
+					quote_spanned!(proc_macro::Span::mixed_site().into() => #definition_crate::VersionControl::Mercurial),
+				);
+				let mut initializer = TokenStream::new();
+				init_value(data, &mut initializer, definition_crate);
+				tokens.append(Group::new(Delimiter::Parenthesis, initializer));
+			}
+			VersionControl::Subversion(data) => {
+				tokens.append_all(
+					quote_spanned!(proc_macro::Span::mixed_site().into() => #definition_crate::VersionControl::Subversion),
+				);
+				let mut initializer = TokenStream::new();
+				init_value(data, &mut initializer, definition_crate);
+				tokens.append(Group::new(Delimiter::Parenthesis, initializer));
+			}
+			VersionControl::CargoVcsInfo(data) => {
+				tokens.append_all(
+					quote_spanned!(proc_macro::Span::mixed_site().into() => #definition_crate::VersionControl::CargoVcsInfo),
+				);
+				let mut initializer = TokenStream::new();
+				init_value(data, &mut initializer, definition_crate);
+				tokens.append(Group::new(Delimiter::Parenthesis, initializer));
+			}
 		}
 	}
 }
@@ -228,6 +484,230 @@ impl InitValue for GitInfo {
 		init_value(&self.tags, &mut initializer, definition_crate);
 		initializer.append_all(quote!(,));
 
+		initializer.append_all(quote!(nearest_tag:));
+		init_value(&self.nearest_tag, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(commits_since_tag:));
+		init_value(&self.commits_since_tag, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(nearest_version_tag:));
+		init_value(&self.nearest_version_tag, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(commits_since_version_tag:));
+		init_value(&self.commits_since_version_tag, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(describe:));
+		init_value(&self.describe, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(crate_tags:));
+		init_value(&self.crate_tags, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(nearest_crate_tag:));
+		init_value(&self.nearest_crate_tag, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(commits_since_crate_tag:));
+		init_value(&self.commits_since_crate_tag, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(crate_tag_version:));
+		init_value(&self.crate_tag_version, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(crate_tag_version_matches:));
+		init_value(&self.crate_tag_version_matches, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(crate_commit_id:));
+		init_value(&self.crate_commit_id, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(crate_commit_timestamp:));
+		init_value(&self.crate_commit_timestamp, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(crate_dirty:));
+		init_value(&self.crate_dirty, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(remotes:));
+		init_value(&self.remotes, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(upstream_remote:));
+		init_value(&self.upstream_remote, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(upstream_branch:));
+		init_value(&self.upstream_branch, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(commits_ahead:));
+		init_value(&self.commits_ahead, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(commits_behind:));
+		init_value(&self.commits_behind, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(author_name:));
+		init_value(&self.author_name, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(author_email:));
+		init_value(&self.author_email, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(committer_name:));
+		init_value(&self.committer_name, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(committer_email:));
+		init_value(&self.committer_email, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(author_timestamp:));
+		init_value(&self.author_timestamp, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(committer_timestamp:));
+		init_value(&self.committer_timestamp, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(summary:));
+		init_value(&self.summary, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(trailers:));
+		init_value(&self.trailers, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(submodules:));
+		init_value(&self.submodules, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		tokens.append(Group::new(Delimiter::Brace, initializer));
+	}
+}
+
+impl InitValue for MercurialInfo {
+	fn init_value(&self, tokens: &mut TokenStream, definition_crate: &Ident) {
+		tokens.append_all(quote_spanned!(proc_macro::Span::mixed_site().into() => #definition_crate::MercurialInfo));
+		let mut initializer = TokenStream::new();
+
+		initializer.append_all(quote!(changeset_id:));
+		init_value(&self.changeset_id, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(revision:));
+		init_value(&self.revision, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(branch:));
+		init_value(&self.branch, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(bookmarks:));
+		init_value(&self.bookmarks, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(tags:));
+		init_value(&self.tags, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(dirty:));
+		init_value(&self.dirty, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		tokens.append(Group::new(Delimiter::Brace, initializer));
+	}
+}
+
+impl InitValue for SubversionInfo {
+	fn init_value(&self, tokens: &mut TokenStream, definition_crate: &Ident) {
+		tokens.append_all(quote_spanned!(proc_macro::Span::mixed_site().into() => #definition_crate::SubversionInfo));
+		let mut initializer = TokenStream::new();
+
+		initializer.append_all(quote!(url:));
+		init_value(&self.url, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(revision:));
+		init_value(&self.revision, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(last_changed_revision:));
+		init_value(&self.last_changed_revision, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(last_changed_author:));
+		init_value(&self.last_changed_author, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(modified:));
+		init_value(&self.modified, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		tokens.append(Group::new(Delimiter::Brace, initializer));
+	}
+}
+
+impl InitValue for CargoVcsInfo {
+	fn init_value(&self, tokens: &mut TokenStream, definition_crate: &Ident) {
+		tokens.append_all(quote_spanned!(proc_macro::Span::mixed_site().into() => #definition_crate::CargoVcsInfo));
+		let mut initializer = TokenStream::new();
+
+		initializer.append_all(quote!(commit_id:));
+		init_value(&self.commit_id, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(commit_short_id:));
+		init_value(&self.commit_short_id, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(dirty:));
+		init_value(&self.dirty, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(path_in_vcs:));
+		init_value(&self.path_in_vcs, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		tokens.append(Group::new(Delimiter::Brace, initializer));
+	}
+}
+
+impl InitValue for SubmoduleInfo {
+	fn init_value(&self, tokens: &mut TokenStream, definition_crate: &Ident) {
+		tokens.append_all(quote_spanned!(proc_macro::Span::mixed_site().into() => #definition_crate::SubmoduleInfo));
+		let mut initializer = TokenStream::new();
+
+		initializer.append_all(quote!(path:));
+		init_value(&self.path, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(commit_id:));
+		init_value(&self.commit_id, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(recorded_commit_id:));
+		init_value(&self.recorded_commit_id, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(matches_superproject:));
+		init_value(&self.matches_superproject, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
+		initializer.append_all(quote!(dirty:));
+		init_value(&self.dirty, &mut initializer, definition_crate);
+		initializer.append_all(quote!(,));
+
 		tokens.append(Group::new(Delimiter::Brace, initializer));
 	}
 }
@@ -243,20 +723,33 @@ impl InitValue for Version {
 
 impl InitValue for DateTime<Utc> {
 	fn init_value(&self, tokens: &mut TokenStream, definition_crate: &Ident) {
-		let nanos = self.timestamp_nanos();
+		let nanos = self.timestamp_nanos_opt().unwrap();
 		tokens.append_all(quote_spanned!(proc_macro::Span::mixed_site().into() =>
 			#definition_crate::chrono::TimeZone::timestamp_nanos(&#definition_crate::chrono::Utc, #nanos)
 		));
 	}
 }
 
+impl InitValue for DateTime<FixedOffset> {
+	fn init_value(&self, tokens: &mut TokenStream, definition_crate: &Ident) {
+		let nanos = self.timestamp_nanos_opt().unwrap();
+		let offset = self.offset().local_minus_utc();
+		tokens.append_all(quote_spanned!(proc_macro::Span::mixed_site().into() =>
+			#definition_crate::chrono::TimeZone::timestamp_nanos(
+				&#definition_crate::chrono::FixedOffset::east_opt(#offset).unwrap(),
+				#nanos
+			)
+		));
+	}
+}
+
 impl InitValue for NaiveDate {
 	fn init_value(&self, tokens: &mut TokenStream, definition_crate: &Ident) {
 		let year = self.year();
 		let month = self.month();
 		let day = self.day();
 		tokens.append_all(quote_spanned!(proc_macro::Span::mixed_site().into() =>
-			#definition_crate::chrono::NaiveDate::from_ymd(#year, #month, #day)
+			#definition_crate::chrono::NaiveDate::from_ymd_opt(#year, #month, #day).unwrap()
 		));
 	}
 }
@@ -273,6 +766,18 @@ impl InitValue for u8 {
 	}
 }
 
+impl InitValue for i64 {
+	fn init_value(&self, tokens: &mut TokenStream, _definition_crate: &Ident) {
+		tokens.append_all(quote!(#self));
+	}
+}
+
+impl InitValue for u32 {
+	fn init_value(&self, tokens: &mut TokenStream, _definition_crate: &Ident) {
+		tokens.append_all(quote!(#self));
+	}
+}
+
 impl InitValue for u64 {
 	fn init_value(&self, tokens: &mut TokenStream, _definition_crate: &Ident) {
 		tokens.append_all(quote!(#self));
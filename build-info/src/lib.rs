@@ -6,7 +6,9 @@
 pub use build_info_common::pyo3::prelude::pyfunction;
 #[cfg(feature = "runtime")]
 pub use build_info_common::{
-	chrono, semver, BuildInfo, CompilerChannel, CompilerInfo, CrateInfo, GitInfo, OptimizationLevel, VersionControl,
+	chrono, semver, BuildInfo, CargoVcsInfo, CompilerChannel, CompilerInfo, CrateInfo, CustomValue, Endianness, GitInfo,
+	MercurialInfo, OptimizationLevel, PanicStrategy, ProfileSettings, SubmoduleInfo, SubversionInfo, TargetInfo,
+	VersionControl,
 };
 /// This crate defines macro_rules that pass `$crate` (i.e., this crate) to the proc-macros doing the actual work
 /// The proc-macro crate that contains said proc-macros is reexported here, to be found in the macro_rules.
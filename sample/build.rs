@@ -1,5 +1,7 @@
 fn main() {
 	// Calling `build_info_build::build_script` collects all data and makes it available to `build_info::build_info!`
 	// and `build_info::format!` in the main program.
-	build_info_build::build_script();
+	//
+	// User-defined values can be attached to the collected data, too.
+	build_info_build::build_script().custom("deploy_channel", "canary");
 }
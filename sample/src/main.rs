@@ -27,6 +27,15 @@ fn main() {
 		build_info::format!("Copyright 2020-{compilation_year}", compilation_year = $.timestamp.format("%Y"))
 	);
 
+	// User-defined values that were set in `build.rs` are available as well
+	println!("{}", build_info::format!("Deploy channel: {}", $.custom.deploy_channel));
+
+	// `?` unwraps optional data, such as the version control information
+	println!(
+		"{}",
+		build_info::format!("Version: {}", $.version_control?.git()?.describe)
+	);
+
 	// Some macros can also be called inside `format!`
 	println!(
 		"{}",